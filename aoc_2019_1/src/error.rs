@@ -0,0 +1,48 @@
+use std::{
+    error,
+    fmt::{self, Debug, Display},
+    io, num,
+};
+
+/// A line which is not a module mass.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct InvalidLine {
+    /// The line number, starting at 1.
+    pub line: usize,
+    pub text: String,
+    pub error: num::ParseIntError,
+}
+
+#[derive(Debug)]
+pub enum Error {
+    IoErr(io::Error),
+    InvalidLines(Vec<InvalidLine>),
+    /// A fuel sum overflowed when adding the module at the index.
+    Overflow {
+        module: usize,
+    },
+}
+
+impl error::Error for Error {}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::IoErr(e) => Display::fmt(e, f),
+            Error::InvalidLines(lines) => {
+                f.write_str("invalid module masses:")?;
+                for l in lines {
+                    write!(f, "\n  line {}: {:?}: {}", l.line, l.text, l.error)?;
+                }
+                Ok(())
+            }
+            Error::Overflow { module } => write!(f, "fuel sum overflowed at module {module}"),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(other: io::Error) -> Self {
+        Error::IoErr(other)
+    }
+}
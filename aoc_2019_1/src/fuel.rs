@@ -0,0 +1,170 @@
+//! Fuel calculations over any unsigned integer width, with overflow checking for sums.
+
+use std::fmt::{Debug, Display};
+
+use crate::error::Error;
+
+/// An unsigned integer type which masses and fuel can be measured in.
+pub trait Mass: Copy + Debug + Default + Display + Ord {
+    /// The fuel for the mass alone, or zero if the mass is too small to need fuel.
+    #[must_use]
+    fn fuel(self) -> Self;
+
+    #[must_use]
+    fn checked_add(self, other: Self) -> Option<Self>;
+
+    #[must_use]
+    fn is_zero(self) -> bool {
+        self == Self::default()
+    }
+}
+
+macro_rules! impl_mass {
+    ($($t:ty),*) => {
+        $(
+            impl Mass for $t {
+                #[inline]
+                fn fuel(self) -> Self {
+                    (self / 3).saturating_sub(2)
+                }
+
+                #[inline]
+                fn checked_add(self, other: Self) -> Option<Self> {
+                    <$t>::checked_add(self, other)
+                }
+            }
+        )*
+    };
+}
+
+impl_mass!(u32, u64, u128);
+
+/// The fuel for the mass and for the fuel itself.
+///
+/// The total is always less than half the mass, so it cannot overflow.
+///
+/// ```
+/// use aoc_2019_1::fuel::total_fuel;
+///
+/// assert_eq!(50346_u64, total_fuel(100_756_u64));
+/// ```
+#[must_use]
+#[inline]
+pub fn total_fuel<T: Mass>(mass: T) -> T {
+    let mut total = T::default();
+    let mut fuel = mass.fuel();
+    while !fuel.is_zero() {
+        total = total
+            .checked_add(fuel)
+            .expect("fuel total to be less than the mass");
+        fuel = fuel.fuel();
+    }
+    total
+}
+
+/// The fuel for the mass, followed by the fuel for each previous step's fuel, until no more
+/// fuel is needed.
+///
+/// ```
+/// use aoc_2019_1::fuel::chain;
+///
+/// assert_eq!(vec![654_u64, 216, 70, 21, 5], chain(1969_u64));
+/// ```
+#[must_use]
+pub fn chain<T: Mass>(mass: T) -> Vec<T> {
+    std::iter::successors(Some(mass.fuel()), |&f| Some(f.fuel()))
+        .take_while(|f| !f.is_zero())
+        .collect()
+}
+
+/// The part 1 and part 2 fuel totals for many modules.
+#[derive(Clone, Copy, Debug, Default, Hash, Eq, PartialEq)]
+pub struct FuelTotals<T> {
+    /// The fuel for the modules' masses alone.
+    pub base: T,
+    /// The fuel for the modules' masses and for the fuel itself.
+    pub total: T,
+}
+
+/// Sums the fuel for every mass in one pass without allocating.
+///
+/// Returns `Error::Overflow` with the index of the module whose fuel made a sum overflow.
+///
+/// ```
+/// use aoc_2019_1::fuel::sum_fuel;
+///
+/// let totals = sum_fuel(vec![12_u64, 1969]).unwrap();
+/// assert_eq!(656, totals.base);
+/// assert_eq!(968, totals.total);
+/// ```
+pub fn sum_fuel<T, I>(masses: I) -> Result<FuelTotals<T>, Error>
+where
+    T: Mass,
+    I: IntoIterator<Item = T>,
+{
+    masses.into_iter().enumerate().try_fold(
+        FuelTotals::default(),
+        |sums: FuelTotals<T>, (module, mass)| {
+            let overflow = || Error::Overflow { module };
+            Ok(FuelTotals {
+                base: sums.base.checked_add(mass.fuel()).ok_or_else(overflow)?,
+                total: sums
+                    .total
+                    .checked_add(total_fuel(mass))
+                    .ok_or_else(overflow)?,
+            })
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryFrom;
+
+    #[test]
+    fn matches_narrow_version() {
+        for mass in (0..200_000).step_by(7) {
+            assert_eq!(
+                crate::fuel_chain(mass).first().copied().unwrap_or(0),
+                mass.fuel()
+            );
+            assert_eq!(
+                u64::from(crate::fuel_chain(mass).iter().sum::<u32>()),
+                total_fuel(u64::from(mass))
+            );
+        }
+    }
+
+    #[test]
+    fn wide_masses() {
+        assert_eq!(u32::MAX / 3 - 2, u32::MAX.fuel());
+        let total = total_fuel(u128::MAX);
+        assert!(total > u128::MAX / 3 && total < u128::MAX / 2);
+        assert_eq!(
+            total_fuel(u64::MAX),
+            u64::try_from(total_fuel(u128::from(u64::MAX))).unwrap()
+        );
+    }
+
+    #[test]
+    fn sums_report_overflow() {
+        let masses = vec![u64::MAX, u64::MAX, u64::MAX, 12];
+        match sum_fuel(masses) {
+            Err(Error::Overflow { module }) => assert_eq!(2, module),
+            r => panic!("unexpected result: {:?}", r),
+        }
+
+        let totals = sum_fuel(vec![u128::from(u64::MAX); 4]).unwrap();
+        assert_eq!(u128::from(u64::MAX.fuel()) * 4, totals.base);
+        assert_eq!(FuelTotals::default(), sum_fuel(Vec::<u32>::new()).unwrap());
+    }
+
+    #[test]
+    fn many_modules() {
+        let masses = (0..1_000_000_u64).map(|i| i * 7919 % 1_000_003);
+        let totals = sum_fuel(masses.clone()).unwrap();
+        assert_eq!(masses.clone().map(Mass::fuel).sum::<u64>(), totals.base);
+        assert_eq!(masses.map(total_fuel).sum::<u64>(), totals.total);
+    }
+}
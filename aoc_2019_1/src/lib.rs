@@ -1,3 +1,11 @@
+use std::fmt::Write;
+
+pub mod error;
+pub mod fuel;
+
+use error::{Error, InvalidLine};
+use fuel::FuelTotals;
+
 /// Calculate the amount of fuel required for a module of a specific mass.
 ///
 /// ```
@@ -7,18 +15,132 @@
 /// ```
 #[must_use]
 pub fn find_fuel_required(mass: u32) -> u32 {
-    let mut result = mass / 3;
-    if result < 2 {
-        0
-    } else {
-        result -= 2;
+    fuel::total_fuel(mass)
+}
+
+/// The fuel for a module's mass, followed by the fuel for each previous step's fuel, until no
+/// more fuel is needed.
+///
+/// ```
+/// use aoc_2019_1::fuel_chain;
+///
+/// assert_eq!(vec![654, 216, 70, 21, 5], fuel_chain(1969));
+/// ```
+#[must_use]
+pub fn fuel_chain(mass: u32) -> Vec<u32> {
+    fuel::chain(mass)
+}
+
+/// The fuel needed for one module.
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub struct ModuleFuel {
+    pub mass: u64,
+    /// The fuel for the mass, then the fuel for each step's fuel.
+    pub chain: Vec<u64>,
+}
 
-        if result == 0 {
-            result
-        } else {
-            result + find_fuel_required(result)
+impl ModuleFuel {
+    #[must_use]
+    pub fn new(mass: u64) -> Self {
+        Self {
+            mass,
+            chain: fuel::chain(mass),
         }
     }
+
+    /// The fuel for the module's mass alone.
+    #[must_use]
+    pub fn base(&self) -> u64 {
+        self.chain.first().copied().unwrap_or(0)
+    }
+
+    /// The fuel for the module's mass and for the fuel itself.
+    ///
+    /// The total is less than half the mass, so it cannot overflow.
+    #[must_use]
+    pub fn total(&self) -> u64 {
+        self.chain.iter().sum()
+    }
+}
+
+/// The fuel needed for every module.
+#[derive(Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub struct FuelReport {
+    pub modules: Vec<ModuleFuel>,
+}
+
+impl FuelReport {
+    pub fn new<I: IntoIterator<Item = u64>>(masses: I) -> Self {
+        Self {
+            modules: masses.into_iter().map(ModuleFuel::new).collect(),
+        }
+    }
+
+    /// The part 1 and part 2 fuel totals, or `Error::Overflow` if a sum does not fit in a `u64`.
+    pub fn totals(&self) -> Result<FuelTotals<u64>, Error> {
+        fuel::sum_fuel(self.modules.iter().map(|m| m.mass))
+    }
+
+    /// A table with a row for each module and a final row of totals.
+    pub fn table(&self) -> Result<String, Error> {
+        let totals = self.totals()?;
+        let mut table = format!(
+            "{:>10} {:>10} {:>12}  chain\n",
+            "mass", "fuel", "total fuel"
+        );
+        for m in &self.modules {
+            let chain = m
+                .chain
+                .iter()
+                .map(u64::to_string)
+                .collect::<Vec<_>>()
+                .join(" ");
+            writeln!(
+                table,
+                "{:>10} {:>10} {:>12}  {}",
+                m.mass,
+                m.base(),
+                m.total(),
+                chain
+            )
+            .expect("writing to a string to succeed");
+        }
+        writeln!(
+            table,
+            "{:>10} {:>10} {:>12}",
+            "total", totals.base, totals.total
+        )
+        .expect("writing to a string to succeed");
+        Ok(table)
+    }
+}
+
+/// Parses one module mass per line, ignoring blank lines.
+///
+/// Returns every invalid line instead of stopping at the first one.
+pub fn parse_masses(input: &str) -> Result<Vec<u64>, Error> {
+    let mut masses = Vec::new();
+    let mut invalid = Vec::new();
+    for (i, line) in input.lines().enumerate() {
+        let text = line.trim();
+        if text.is_empty() {
+            continue;
+        }
+        match text.parse() {
+            Ok(mass) => masses.push(mass),
+            Err(error) => invalid.push(InvalidLine {
+                line: i + 1,
+                text: text.to_string(),
+                error,
+            }),
+        }
+    }
+
+    if invalid.is_empty() {
+        Ok(masses)
+    } else {
+        Err(Error::InvalidLines(invalid))
+    }
 }
 
 #[cfg(test)]
@@ -48,4 +170,70 @@ mod test {
         let mass = 100_756;
         assert_eq!(50346, find_fuel_required(mass));
     }
+
+    #[test]
+    fn chains() {
+        assert_eq!(vec![2], fuel_chain(12));
+        assert_eq!(Vec::<u32>::new(), fuel_chain(8));
+        assert_eq!(vec![654, 216, 70, 21, 5], fuel_chain(1969));
+        assert_eq!(
+            find_fuel_required(100_756),
+            fuel_chain(100_756).iter().sum::<u32>()
+        );
+    }
+
+    #[test]
+    fn report() {
+        let totals = FuelReport::new(vec![12, 14, 1969, 100_756])
+            .totals()
+            .unwrap();
+        assert_eq!(2 + 2 + 654 + 33583, totals.base);
+        assert_eq!(2 + 2 + 966 + 50346, totals.total);
+        let expected = [
+            "      mass       fuel   total fuel  chain",
+            "        12          2            2  2",
+            "      1969        654          966  654 216 70 21 5",
+            "     total        656          968",
+            "",
+        ];
+        assert_eq!(
+            expected.join("\n"),
+            FuelReport::new(vec![12, 1969]).table().unwrap()
+        );
+    }
+
+    #[test]
+    fn large_masses() {
+        let masses = parse_masses("5000000000\n12\n").unwrap();
+        assert_eq!(vec![5_000_000_000, 12], masses);
+
+        let report = FuelReport::new(masses);
+        let totals = report.totals().unwrap();
+        assert_eq!(1_666_666_664 + 2, totals.base);
+        assert_eq!(report.modules[0].total() + 2, totals.total);
+        assert!(report.table().unwrap().contains("5000000000 1666666664"));
+
+        match FuelReport::new(vec![u64::MAX; 3]).table() {
+            Err(Error::Overflow { module }) => assert_eq!(2, module),
+            r => panic!("unexpected result: {:?}", r),
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(vec![12, 14], parse_masses("12\n\n 14 \n").unwrap());
+
+        match parse_masses("12\nabc\n14\n-3\n") {
+            Err(Error::InvalidLines(lines)) => {
+                assert_eq!(
+                    vec![(2, "abc"), (4, "-3")],
+                    lines
+                        .iter()
+                        .map(|l| (l.line, l.text.as_str()))
+                        .collect::<Vec<_>>()
+                );
+            }
+            r => panic!("unexpected result: {:?}", r),
+        }
+    }
 }
@@ -1,32 +1,41 @@
-use std::io::{self};
+use std::env;
+use std::io::{self, Read};
+use std::process;
 
-use aoc_2019_1::find_fuel_required;
+use aoc_2019_1::{error::Error, FuelReport};
 
-fn main() {
-    let mut total_fuel_req: u32 = 0;
-    loop {
-        let mut input = String::new();
-        match io::stdin().read_line(&mut input) {
-            Ok(n) => {
-                if n == 0 {
-                    break;
-                }
+/// Reads one module mass per line from stdin and prints the part 1 and part 2 fuel totals.
+///
+/// `--table` prints the fuel for each module instead.
+fn main() -> Result<(), Error> {
+    let table = match env::args().nth(1).as_deref() {
+        None => false,
+        Some("--table") => true,
+        Some(_) => {
+            eprintln!("usage: aoc_2019_1 [--table] < input");
+            process::exit(2);
+        }
+    };
+
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+    let report = aoc_2019_1::parse_masses(&input).map(FuelReport::new);
+    let output = report.and_then(|report| {
+        if table {
+            report.table()
+        } else {
+            let totals = report.totals()?;
+            Ok(format!("{}\n{}\n", totals.base, totals.total))
+        }
+    });
 
-                match input.trim().parse::<u32>() {
-                    Ok(mass) => {
-                        total_fuel_req += find_fuel_required(mass);
-                    }
-                    Err(e) => {
-                        eprintln!("input: {input}, error: {e}");
-                        break;
-                    }
-                };
-            }
-            Err(e) => {
-                eprintln!("error: {e}");
-                break;
-            }
+    match output {
+        Ok(output) => print!("{output}"),
+        Err(e) => {
+            eprintln!("{e}");
+            process::exit(1);
         }
     }
-    println!("{total_fuel_req}");
+
+    Ok(())
 }
@@ -11,6 +11,7 @@ pub enum Error {
     TryFromIntError(num::TryFromIntError),
     NoAvailableInput,
     UnknownValue,
+    Ocr(aoc_ocr::error::Error),
 }
 
 impl error::Error for Error {}
@@ -23,6 +24,7 @@ impl Display for Error {
             Error::TryFromIntError(e) => Display::fmt(e, f),
             Error::NoAvailableInput => f.write_str("no available input"),
             Error::UnknownValue => f.write_str("unknown value"),
+            Error::Ocr(e) => Display::fmt(e, f),
         }
     }
 }
@@ -44,3 +46,9 @@ impl From<num::TryFromIntError> for Error {
         Error::TryFromIntError(other)
     }
 }
+
+impl From<aoc_ocr::error::Error> for Error {
+    fn from(other: aoc_ocr::error::Error) -> Self {
+        Error::Ocr(other)
+    }
+}
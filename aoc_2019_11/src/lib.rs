@@ -138,6 +138,14 @@ impl Prog {
             state: ProgState::NotStarted,
         }
     }
+
+    #[must_use] pub fn mem_state(&self) -> &[i64] {
+        &self.mem_state
+    }
+
+    #[must_use] pub fn state(&self) -> ProgState {
+        self.state
+    }
 }
 
 impl Prog {
@@ -617,6 +625,29 @@ pub fn display_panels(mut panels: Vec<Panel>) {
     }
 }
 
+/// Reads the letters painted in white, with the largest `y` at the top.
+pub fn read_panels(panels: &[Panel]) -> Result<String, Error> {
+    let (min_x, max_x, min_y, max_y) = match (
+        panels.iter().map(|p| p.pos.x).min(),
+        panels.iter().map(|p| p.pos.x).max(),
+        panels.iter().map(|p| p.pos.y).min(),
+        panels.iter().map(|p| p.pos.y).max(),
+    ) {
+        (Some(min_x), Some(max_x), Some(min_y), Some(max_y)) => (min_x, max_x, min_y, max_y),
+        _ => return Ok(String::new()),
+    };
+
+    let width = usize::try_from(max_x - min_x + 1)?;
+    let height = usize::try_from(max_y - min_y + 1)?;
+    let mut pixels = vec![false; width * height];
+    for panel in panels.iter().filter(|p| p.color == Color::White) {
+        let x = usize::try_from(panel.pos.x - min_x)?;
+        let y = usize::try_from(max_y - panel.pos.y)?;
+        pixels[y * width + x] = true;
+    }
+    Ok(aoc_ocr::recognize(&pixels, width)?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1265,4 +1296,36 @@ mod tests {
 
         assert_eq!(vec![1_125_899_906_842_624_i64.to_string()], test_output.output);
     }
+
+    #[test]
+    fn read_letters() {
+        let art = [
+            "..##.#...",
+            "...#.#...",
+            "...#.#...",
+            "...#.#...",
+            "#..#.#...",
+            ".##..####",
+        ];
+        // The top row has the largest `y`, and the left column is at `x = -1`.
+        let panels = (0..)
+            .zip(art.iter())
+            .flat_map(|(row, line)| {
+                (0..).zip(line.chars()).map(move |(col, ch)| Panel {
+                    pos: Pos {
+                        x: col - 1,
+                        y: 2 - row,
+                    },
+                    color: if ch == '#' {
+                        Color::White
+                    } else {
+                        Color::Black
+                    },
+                })
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!("JL", read_panels(&panels).unwrap());
+        assert_eq!("", read_panels(&[]).unwrap());
+    }
 }
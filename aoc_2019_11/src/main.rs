@@ -9,7 +9,7 @@ fn main() -> Result<(), Error> {
 
     let prog = Prog::new(&mem_state);
     let panels = aoc_2019_11::paint_hull(prog)?;
-    aoc_2019_11::display_panels(panels);
+    println!("{}", aoc_2019_11::read_panels(&panels)?);
 
     Ok(())
 }
@@ -1,8 +1,8 @@
-use std::collections::VecDeque;
 use std::ops::Range;
 
 use crate::error::Error;
-use crate::intcode::{Prog, ProgState};
+use crate::exec::{channel, LocalExecutor};
+use crate::intcode::Prog;
 
 fn build_input(existing_input: &[i64], rng: Range<i64>, count: i64) -> Vec<Vec<i64>> {
     if count <= 0 {
@@ -74,44 +74,41 @@ fn run_amplifiers_in_feedback_loop(
     init_mem_state: &[i64],
     inputs: &[i64],
 ) -> Result<Option<i64>, Error> {
-    struct Amp {
-        prog: Prog,
-        prog_input: VecDeque<String>,
-    }
-
-    let mut amps = Vec::<Amp>::with_capacity(inputs.len());
-    for input in inputs {
-        let mut mem_state = vec![0; init_mem_state.len()];
-        mem_state.copy_from_slice(init_mem_state);
-
-        let mut prog_input = VecDeque::new();
-        prog_input.push_back(input.to_string());
+    let mut executor = LocalExecutor::new();
+    let (senders, receivers): (Vec<_>, Vec<_>) = inputs.iter().map(|_| channel()).unzip();
 
-        amps.push(Amp {
-            prog: Prog::new(&mem_state),
-            prog_input,
-        });
+    for (sender, input) in senders.iter().zip(inputs) {
+        sender.send(*input);
     }
+    senders[0].send(0);
+
+    let amps = receivers
+        .into_iter()
+        .enumerate()
+        .map(|(idx, mut prog_input)| {
+            let prog_output = senders[(idx + 1) % senders.len()].clone();
+            executor.spawn(async move {
+                let mut prog = Prog::new(init_mem_state);
+                prog.run_async(&mut prog_input, &prog_output).await?;
+                Ok::<_, Error>(prog_input)
+            })
+        })
+        .collect::<Vec<_>>();
 
-    amps[0].prog_input.push_back(0.to_string());
-
-    let mut prog_output = VecDeque::<String>::new();
-    loop {
-        for amp in &mut amps {
-            for o in &prog_output {
-                amp.prog_input.push_back(o.to_string());
-            }
-
-            prog_output = VecDeque::<String>::new();
-
-            amp.prog.run(&mut amp.prog_input, &mut prog_output)?;
-        }
+    if executor.run() > 0 {
+        return Ok(None);
+    }
 
-        if amps[amps.len() - 1].prog.state() == ProgState::Halt {
-            assert!(amps.iter().all(|a| a.prog.state() == ProgState::Halt));
-            return Ok(Some(prog_output.pop_front().unwrap().parse::<i64>()?));
-        }
+    // The last amp's output is left in the first amp's input.
+    let mut results = amps
+        .iter()
+        .map(|a| a.try_take().expect("amp to have completed"));
+    let mut first_input = results.next().expect("at least one amp")?;
+    for result in results {
+        result?;
     }
+
+    Ok(first_input.try_recv())
 }
 
 #[cfg(test)]
@@ -1,8 +1,14 @@
-use std::cmp::Ordering;
 use std::collections::VecDeque;
+use std::convert::TryFrom;
+use std::fmt;
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::PathBuf;
 use std::str::FromStr;
 
-use crate::error::Error;
+use crate::controller::{toward, Controller};
+use crate::error::{Error, ProtocolError};
+use crate::image::{Format, Image, Palette, Rgb};
 use crate::intcode::{Prog, ProgState};
 
 #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
@@ -24,15 +30,37 @@ impl FromStr for Type {
             "2" => Ok(Type::Block),
             "3" => Ok(Type::HorizontalPaddle),
             "4" => Ok(Type::Ball),
-            _ => Err(Error::UnknownValue),
+            _ => Err(ProtocolError::UnknownTileId(s.to_string()).into()),
         }
     }
 }
 
-#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+impl Type {
+    fn index(self) -> usize {
+        match self {
+            Type::Empty => 0,
+            Type::Wall => 1,
+            Type::Block => 2,
+            Type::HorizontalPaddle => 3,
+            Type::Ball => 4,
+        }
+    }
+
+    fn glyph(self) -> char {
+        match self {
+            Type::Empty => ' ',
+            Type::Wall => '|',
+            Type::Block => '=',
+            Type::HorizontalPaddle => '-',
+            Type::Ball => '*',
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq)]
 pub struct Pos {
-    x: i64,
-    y: i64,
+    pub x: i64,
+    pub y: i64,
 }
 
 #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
@@ -48,6 +76,19 @@ pub enum Joystick {
     Right,
 }
 
+impl FromStr for Joystick {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "0" => Ok(Joystick::Neutral),
+            "-1" => Ok(Joystick::Left),
+            "1" => Ok(Joystick::Right),
+            _ => Err(Error::UnknownValue),
+        }
+    }
+}
+
 impl From<Joystick> for String {
     fn from(other: Joystick) -> String {
         match other {
@@ -58,119 +99,880 @@ impl From<Joystick> for String {
     }
 }
 
-pub fn run_loop(mut prog: Prog) -> Result<i64, Error> {
+/// The tiles drawn by the game, along with the score.
+///
+/// Tiles are stored in a dense grid which grows to cover every drawn position. The grid may be
+/// larger than the drawn area.
+#[derive(Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub struct Screen {
+    /// The position of the grid's first cell.
+    origin: Pos,
+    width: usize,
+    height: usize,
+    cells: Vec<Option<Type>>,
+    /// The minimum and maximum drawn positions (inclusive).
+    drawn: Option<(Pos, Pos)>,
+    counts: [usize; 5],
+    paddle: Option<Pos>,
+    ball: Option<Pos>,
+    score: i64,
+}
+
+fn to_i64(n: usize) -> i64 {
+    i64::try_from(n).expect("screen size to fit in i64")
+}
+
+impl Screen {
+    #[must_use]
+    pub fn new() -> Self {
+        Screen::default()
+    }
+
+    /// The minimum and maximum drawn positions (inclusive).
+    #[must_use]
+    pub fn bounds(&self) -> Option<(Pos, Pos)> {
+        self.drawn
+    }
+
+    /// The minimum and maximum positions the grid can hold (inclusive).
+    fn allocated(&self) -> Option<(Pos, Pos)> {
+        if self.cells.is_empty() {
+            return None;
+        }
+        Some((
+            self.origin,
+            Pos {
+                x: self.origin.x + to_i64(self.width) - 1,
+                y: self.origin.y + to_i64(self.height) - 1,
+            },
+        ))
+    }
+
+    fn index(&self, pos: Pos) -> Option<usize> {
+        let x = usize::try_from(pos.x - self.origin.x).ok()?;
+        let y = usize::try_from(pos.y - self.origin.y).ok()?;
+        if x < self.width && y < self.height {
+            Some(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    /// Grows the grid to contain the position, at least doubling a dimension which grows.
+    fn grow(&mut self, pos: Pos) {
+        let (min, max) = match self.allocated() {
+            Some((min, max)) => (min, max),
+            None => (pos, pos),
+        };
+        let mut new_min = Pos {
+            x: min.x.min(pos.x),
+            y: min.y.min(pos.y),
+        };
+        let mut new_max = Pos {
+            x: max.x.max(pos.x),
+            y: max.y.max(pos.y),
+        };
+        if !self.cells.is_empty() {
+            let width = to_i64(self.width);
+            let height = to_i64(self.height);
+            if pos.x < min.x {
+                new_min.x = new_min.x.min(min.x - width);
+            } else if pos.x > max.x {
+                new_max.x = new_max.x.max(max.x + width);
+            }
+            if pos.y < min.y {
+                new_min.y = new_min.y.min(min.y - height);
+            } else if pos.y > max.y {
+                new_max.y = new_max.y.max(max.y + height);
+            }
+        }
+
+        let mut grown = Screen {
+            origin: new_min,
+            width: usize::try_from(new_max.x - new_min.x + 1).expect("width to fit in usize"),
+            height: usize::try_from(new_max.y - new_min.y + 1).expect("height to fit in usize"),
+            cells: Vec::new(),
+            drawn: self.drawn,
+            counts: self.counts,
+            paddle: self.paddle,
+            ball: self.ball,
+            score: self.score,
+        };
+        grown.cells = vec![None; grown.width * grown.height];
+        for (pos, tile_id) in self.tiles() {
+            let idx = grown.index(pos).expect("position to be in grown bounds");
+            grown.cells[idx] = Some(tile_id);
+        }
+        *self = grown;
+    }
+
+    /// The tile at a position, if one has been drawn.
+    #[must_use]
+    pub fn get(&self, pos: Pos) -> Option<Type> {
+        self.index(pos).and_then(|idx| self.cells[idx])
+    }
+
+    /// Draws a tile at a position, replacing any existing tile.
+    pub fn set(&mut self, tile: Tile) {
+        let idx = if let Some(idx) = self.index(tile.pos) {
+            idx
+        } else {
+            self.grow(tile.pos);
+            self.index(tile.pos).expect("position to be in grown bounds")
+        };
+
+        self.drawn = Some(match self.drawn {
+            Some((min, max)) => (
+                Pos {
+                    x: min.x.min(tile.pos.x),
+                    y: min.y.min(tile.pos.y),
+                },
+                Pos {
+                    x: max.x.max(tile.pos.x),
+                    y: max.y.max(tile.pos.y),
+                },
+            ),
+            None => (tile.pos, tile.pos),
+        });
+
+        if let Some(prev) = self.cells[idx].replace(tile.tile_id) {
+            self.counts[prev.index()] -= 1;
+        }
+        self.counts[tile.tile_id.index()] += 1;
+
+        if self.paddle == Some(tile.pos) {
+            self.paddle = None;
+        }
+        if self.ball == Some(tile.pos) {
+            self.ball = None;
+        }
+        match tile.tile_id {
+            Type::HorizontalPaddle => self.paddle = Some(tile.pos),
+            Type::Ball => self.ball = Some(tile.pos),
+            Type::Empty | Type::Wall | Type::Block => {}
+        }
+    }
+
+    /// The number of drawn tiles of a type.
+    #[must_use]
+    pub fn count(&self, tile_id: Type) -> usize {
+        self.counts[tile_id.index()]
+    }
+
+    #[must_use]
+    pub fn blocks_remaining(&self) -> usize {
+        self.count(Type::Block)
+    }
+
+    #[must_use]
+    pub fn paddle(&self) -> Option<Pos> {
+        self.paddle
+    }
+
+    #[must_use]
+    pub fn ball(&self) -> Option<Pos> {
+        self.ball
+    }
+
+    #[must_use]
+    pub fn score(&self) -> i64 {
+        self.score
+    }
+
+    /// Every drawn tile in row order.
+    pub fn tiles(&self) -> impl Iterator<Item = (Pos, Type)> + '_ {
+        self.cells.iter().enumerate().filter_map(move |(idx, t)| {
+            t.map(|t| {
+                (
+                    Pos {
+                        x: self.origin.x + to_i64(idx % self.width),
+                        y: self.origin.y + to_i64(idx / self.width),
+                    },
+                    t,
+                )
+            })
+        })
+    }
+
+    /// Applies every `x, y, tile id` output triple, updating tiles and the score.
+    ///
+    /// Nothing is applied if the output does not contain a whole number of triples.
+    pub fn update(&mut self, output: &mut VecDeque<String>) -> Result<(), Error> {
+        if !output.len().is_multiple_of(3) {
+            return Err(ProtocolError::TruncatedOutput {
+                expected: 3,
+                received: output.len() % 3,
+            }
+            .into());
+        }
+
+        while let (Some(x), Some(y), Some(id)) =
+            (output.pop_front(), output.pop_front(), output.pop_front())
+        {
+            if x == "-1" && y == "0" {
+                self.score = id.parse::<i64>()?;
+            } else {
+                self.set(Tile {
+                    pos: Pos {
+                        x: x.parse::<i64>()?,
+                        y: y.parse::<i64>()?,
+                    },
+                    tile_id: Type::from_str(&id)?,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Draws the screen with the smallest `y` at the top, as the game does, so the walls are above
+/// the paddle.
+///
+/// Earlier versions printed the largest `y` first, which drew the game upside down.
+impl fmt::Display for Screen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (min, max) = match self.bounds() {
+            Some(bounds) => bounds,
+            None => return Ok(()),
+        };
+        for y in min.y..=max.y {
+            let row = (min.x..=max.x)
+                .map(|x| self.get(Pos { x, y }).map_or(' ', Type::glyph))
+                .collect::<String>();
+            writeln!(f, "{}", row.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+/// The result of playing a game to completion.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct GameResult {
+    pub score: i64,
+    /// The number of joystick inputs given to the program.
+    pub inputs: usize,
+}
+
+/// A game paused between joystick inputs.
+///
+/// Cloning a game saves its state, including the program, so play can continue from it later.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Game {
+    prog: Prog,
+    screen: Screen,
+    inputs: usize,
+}
+
+impl Game {
+    /// Runs the program until it first waits for input or halts.
+    pub fn new(prog: Prog) -> Result<Self, Error> {
+        let mut game = Game {
+            prog,
+            screen: Screen::new(),
+            inputs: 0,
+        };
+        game.advance(VecDeque::new())?;
+        Ok(game)
+    }
+
+    #[must_use]
+    pub fn screen(&self) -> &Screen {
+        &self.screen
+    }
+
+    /// The number of joystick inputs given to the program.
+    #[must_use]
+    pub fn inputs(&self) -> usize {
+        self.inputs
+    }
+
+    #[must_use]
+    pub fn is_over(&self) -> bool {
+        self.prog.state() == ProgState::Halt
+    }
+
+    #[must_use]
+    pub fn result(&self) -> GameResult {
+        GameResult {
+            score: self.screen.score(),
+            inputs: self.inputs,
+        }
+    }
+
+    fn advance(&mut self, mut input: VecDeque<String>) -> Result<(), Error> {
+        let mut output = VecDeque::new();
+        self.prog.run(&mut input, &mut output)?;
+        self.screen.update(&mut output)?;
+
+        match self.prog.state() {
+            ProgState::Halt | ProgState::NeedInput => Ok(()),
+            ProgState::NotStarted | ProgState::Running => unreachable!(),
+        }
+    }
+
+    /// Gives the joystick input and runs until the program waits for input again or halts.
+    ///
+    /// Returns `Error::GameOver` if the program has already halted.
+    pub fn input(&mut self, joystick: Joystick) -> Result<(), Error> {
+        if self.is_over() {
+            return Err(Error::GameOver);
+        }
+        self.inputs += 1;
+        self.advance(VecDeque::from(vec![String::from(joystick)]))
+    }
+}
+
+/// The most recent game states, one for each joystick input.
+///
+/// The oldest state is dropped when a state is pushed at capacity.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SaveStates {
+    states: VecDeque<Game>,
+    capacity: usize,
+}
+
+impl SaveStates {
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        SaveStates {
+            states: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    pub fn push(&mut self, game: &Game) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.states.len() == self.capacity {
+            self.states.pop_front();
+        }
+        self.states.push_back(game.clone());
+    }
+
+    /// Returns the state from before the last `n` inputs, dropping every later state so play
+    /// can branch from it.
+    ///
+    /// Rewinds to the oldest saved state if fewer than `n` inputs were saved.
+    pub fn rewind(&mut self, n: usize) -> Option<Game> {
+        let n = n.clamp(1, self.states.len().max(1));
+        self.states.truncate(self.states.len() - (n - 1).min(self.states.len()));
+        self.states.pop_back()
+    }
+}
+
+/// Runs a program which draws the screen and halts without reading input.
+pub fn draw_screen(mut prog: Prog) -> Result<Screen, Error> {
     let mut input = VecDeque::new();
     let mut output = VecDeque::new();
+    prog.run(&mut input, &mut output)?;
 
-    let mut score: i64 = 0;
-    let mut tiles = Vec::<Tile>::new();
+    let mut screen = Screen::new();
+    screen.update(&mut output)?;
+    match prog.state() {
+        ProgState::Halt => Ok(screen),
+        ProgState::NeedInput => Err(ProtocolError::UnexpectedInputRequest.into()),
+        ProgState::NotStarted | ProgState::Running => unreachable!(),
+    }
+}
+
+pub fn run_loop<C: Controller>(prog: Prog, controller: &mut C) -> Result<GameResult, Error> {
+    run_loop_with_frames(prog, controller, |_| Ok(()))
+}
+
+/// Plays a game, calling `on_frame` with the screen each time the program waits for input.
+pub fn run_loop_with_frames<C, F>(
+    prog: Prog,
+    controller: &mut C,
+    mut on_frame: F,
+) -> Result<GameResult, Error>
+where
+    C: Controller,
+    F: FnMut(&Screen) -> Result<(), Error>,
+{
+    let mut game = Game::new(prog)?;
+    while !game.is_over() {
+        on_frame(game.screen())?;
+        let joystick = controller.joystick(game.screen())?;
+        game.input(joystick)?;
+    }
+    Ok(game.result())
+}
+
+/// Plays a game, rewinding and steering toward the ball's landing column when a ball is missed.
+///
+/// A save state is kept for each of the last `capacity` inputs. After a miss, the game is
+/// rewound to just after the ball last passed the paddle's row and the paddle is moved to
+/// where the ball came down. Returns the lost game's result if the same ball is missed again,
+/// stopping as soon as the ball is past the paddle.
+pub fn run_loop_with_rewind<C: Controller>(
+    prog: Prog,
+    controller: &mut C,
+    capacity: usize,
+) -> Result<GameResult, Error> {
+    let mut states = SaveStates::new(capacity);
+    let mut game = Game::new(prog)?;
+    // The input count and column each time the ball reached the row above the paddle.
+    let mut above_paddle: Vec<(usize, i64)> = Vec::new();
+    let mut target = None;
+    let mut retry_of = None;
 
     loop {
-        let mut new_tiles = Vec::<Tile>::new();
-        prog.run(&mut input, &mut output)?;
-
-        while let Some(x) = output.pop_front() {
-            if let Some(y) = output.pop_front() {
-                if let Some(id) = output.pop_front() {
-                    if x == "-1" && y == "0" {
-                        score = id.parse::<i64>()?;
-                    } else {
-                        new_tiles.push(Tile {
-                            pos: Pos {
-                                x: x.parse::<i64>()?,
-                                y: y.parse::<i64>()?,
-                            },
-                            tile_id: Type::from_str(&id)?,
-                        });
+        let screen = game.screen();
+        // Some programs never halt once the ball is past the paddle near a wall.
+        let is_past_paddle = match (screen.paddle(), screen.ball()) {
+            (Some(paddle), Some(ball)) => ball.y >= paddle.y,
+            _ => false,
+        };
+        if game.is_over() || is_past_paddle {
+            let missed = screen.blocks_remaining() > 0;
+            match above_paddle.last().copied() {
+                Some((at, x)) if missed && retry_of != Some(at) && !states.is_empty() => {
+                    let since = above_paddle
+                        .iter()
+                        .rev()
+                        .nth(1)
+                        .map_or(usize::MAX, |&(prev, _)| game.inputs() - prev - 1);
+                    game = states.rewind(since).expect("save state to exist");
+                    above_paddle.retain(|&(i, _)| i < game.inputs());
+                    target = Some(x);
+                    retry_of = Some(at);
+                    continue;
+                }
+                _ => return Ok(game.result()),
+            }
+        }
+
+        states.push(&game);
+
+        let joystick = match (screen.paddle(), screen.ball()) {
+            (Some(paddle), Some(ball)) => {
+                let is_above_paddle = ball.y == paddle.y - 1;
+                if is_above_paddle {
+                    above_paddle.push((game.inputs(), ball.x));
+                }
+                match target {
+                    Some(x) => {
+                        if is_above_paddle {
+                            target = None;
+                        }
+                        toward(paddle.x, x)
                     }
-                } else {
-                    panic!("unexpected program state");
+                    None => controller.joystick(screen)?,
                 }
-            } else {
-                panic!("unexpected program state");
             }
+            _ => controller.joystick(screen)?,
+        };
+        game.input(joystick)?;
+    }
+}
+
+/// Prints the screen with the smallest `y` at the top.
+pub fn display(screen: &Screen) {
+    print!("{screen}");
+}
+
+/// The palette used by the puzzle description's glyphs as colors.
+#[must_use]
+pub fn default_palette() -> Palette<Type> {
+    Palette::new(Rgb(0, 0, 0))
+        .with(Type::Wall, Rgb(128, 128, 128))
+        .with(Type::Block, Rgb(64, 128, 255))
+        .with(Type::HorizontalPaddle, Rgb(255, 255, 255))
+        .with(Type::Ball, Rgb(255, 96, 64))
+}
+
+/// Renders the screen as an image, drawing each tile as a `scale` by `scale` square.
+#[must_use]
+pub fn render(screen: &Screen, palette: &Palette<Type>, scale: usize) -> Image {
+    match screen.bounds() {
+        Some((min, max)) => Image::from_cells(
+            usize::try_from(max.x - min.x + 1).expect("width to fit in usize"),
+            usize::try_from(max.y - min.y + 1).expect("height to fit in usize"),
+            scale,
+            |x, y| {
+                palette.color(
+                    screen
+                        .get(Pos {
+                            x: min.x + to_i64(x),
+                            y: min.y + to_i64(y),
+                        })
+                        .as_ref(),
+                )
+            },
+        ),
+        None => Image::from_cells(0, 0, scale, |_, _| palette.color(None)),
+    }
+}
+
+/// Writes numbered image files for each frame passed to `write_frame`.
+#[derive(Clone, Debug)]
+pub struct FrameDump {
+    dir: PathBuf,
+    format: Format,
+    palette: Palette<Type>,
+    scale: usize,
+    frames: usize,
+}
+
+impl FrameDump {
+    pub fn new<P: Into<PathBuf>>(
+        dir: P,
+        format: Format,
+        palette: Palette<Type>,
+        scale: usize,
+    ) -> Self {
+        FrameDump {
+            dir: dir.into(),
+            format,
+            palette,
+            scale,
+            frames: 0,
         }
+    }
 
-        let pos_to_replace = new_tiles.iter().map(|t| t.pos).collect::<Vec<Pos>>();
+    /// The number of frames written.
+    #[must_use]
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
 
-        tiles = tiles
-            .into_iter()
-            .filter(|t| !pos_to_replace.contains(&t.pos))
-            .collect::<Vec<Tile>>();
+    /// Writes the screen to `frame_NNNNN.<ext>` in the directory.
+    pub fn write_frame(&mut self, screen: &Screen) -> Result<(), Error> {
+        let path = self
+            .dir
+            .join(format!("frame_{:05}.{}", self.frames, self.format.extension()));
+        let file = BufWriter::new(File::create(path)?);
+        render(screen, &self.palette, self.scale).write(self.format, file)?;
+        self.frames += 1;
+        Ok(())
+    }
+}
 
-        tiles.append(&mut new_tiles);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        // display(tiles.clone());
+    fn tile(x: i64, y: i64, tile_id: Type) -> Tile {
+        Tile {
+            pos: Pos { x, y },
+            tile_id,
+        }
+    }
 
-        match prog.state() {
-            ProgState::Halt => break,
-            ProgState::NotStarted => unreachable!(),
-            ProgState::NeedInput => {
-                let paddle = tiles
-                    .iter()
-                    .find(|&&t| t.tile_id == Type::HorizontalPaddle)
-                    .expect("paddle to exist");
+    #[test]
+    fn screen_tracks_tiles() {
+        let mut screen = Screen::new();
+        let mut output = ["1", "0", "1", "0", "0", "2", "1", "1", "4", "-1", "0", "12"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<VecDeque<_>>();
+        screen.update(&mut output).unwrap();
 
-                let ball = tiles
-                    .iter()
-                    .find(|&&t| t.tile_id == Type::Ball)
-                    .expect("ball to exist");
+        assert_eq!(12, screen.score());
+        assert_eq!(1, screen.blocks_remaining());
+        assert_eq!(Some(Pos { x: 1, y: 1 }), screen.ball());
+        assert_eq!(None, screen.paddle());
+        assert_eq!(Some(Type::Wall), screen.get(Pos { x: 1, y: 0 }));
+        assert_eq!(None, screen.get(Pos { x: 0, y: 1 }));
+        assert_eq!(
+            Some((Pos { x: 0, y: 0 }, Pos { x: 1, y: 1 })),
+            screen.bounds()
+        );
 
-                if ball.pos.x == paddle.pos.x {
-                    input.push_back(String::from(Joystick::Neutral));
-                } else if ball.pos.x > paddle.pos.x {
-                    input.push_back(String::from(Joystick::Right));
-                } else if ball.pos.x < paddle.pos.x {
-                    input.push_back(String::from(Joystick::Left));
-                }
+        screen.set(tile(0, 0, Type::Empty));
+        screen.set(tile(1, 1, Type::Empty));
+        screen.set(tile(2, 3, Type::Ball));
+        assert_eq!(0, screen.blocks_remaining());
+        assert_eq!(2, screen.count(Type::Empty));
+        assert_eq!(Some(Pos { x: 2, y: 3 }), screen.ball());
+        assert_eq!(Some(Type::Wall), screen.get(Pos { x: 1, y: 0 }));
+        assert_eq!(Some(Type::Ball), screen.get(Pos { x: 2, y: 3 }));
+        assert_eq!(
+            [
+                Type::Empty,
+                Type::Wall,
+                Type::Block,
+                Type::HorizontalPaddle,
+                Type::Ball
+            ]
+            .iter()
+            .map(|&t| screen.count(t))
+            .sum::<usize>(),
+            screen.tiles().count()
+        );
+    }
+
+    #[test]
+    fn screen_grows_in_every_direction() {
+        let mut screen = Screen::new();
+        screen.set(tile(5, 5, Type::Block));
+        screen.set(tile(-2, 7, Type::HorizontalPaddle));
+        screen.set(tile(9, -1, Type::Wall));
+
+        assert_eq!(Some(Type::Block), screen.get(Pos { x: 5, y: 5 }));
+        assert_eq!(Some(Pos { x: -2, y: 7 }), screen.paddle());
+        assert_eq!(
+            Some((Pos { x: -2, y: -1 }, Pos { x: 9, y: 7 })),
+            screen.bounds()
+        );
+        assert_eq!(
+            vec![
+                (Pos { x: 9, y: -1 }, Type::Wall),
+                (Pos { x: 5, y: 5 }, Type::Block),
+                (Pos { x: -2, y: 7 }, Type::HorizontalPaddle),
+            ],
+            screen.tiles().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            "           |\n\n\n\n\n\n       =\n\n-\n",
+            screen.to_string()
+        );
+
+        // Growing right and down doubles the grid, but only the drawn area is shown.
+        let mut screen = Screen::new();
+        for y in 0..3 {
+            for x in 0..5 {
+                screen.set(tile(x, y, Type::Wall));
             }
         }
+        assert_eq!(
+            Some((Pos { x: 0, y: 0 }, Pos { x: 4, y: 2 })),
+            screen.bounds()
+        );
+        assert_eq!("|||||\n|||||\n|||||\n", screen.to_string());
     }
 
-    Ok(score)
-}
+    #[test]
+    fn run_loop_with_controller() {
+        use crate::controller::{FollowBall, Scripted};
 
-pub fn display(mut tiles: Vec<Tile>) {
-    let max_x = tiles.iter().map(|t| t.pos.x).max().unwrap();
-    let min_x = tiles.iter().map(|t| t.pos.x).min().unwrap();
-    let max_y = tiles.iter().map(|t| t.pos.y).max().unwrap();
-    let min_y = tiles.iter().map(|t| t.pos.y).min().unwrap();
+        // Draws a paddle and a ball, then outputs the joystick input as the score.
+        let mem_state = vec![
+            104, 1, 104, 2, 104, 3, 104, 2, 104, 1, 104, 4, 3, 100, 104, -1, 104, 0, 4, 100, 99,
+        ];
 
-    tiles.sort_by(|a, b| {
-        if a.pos.y > b.pos.y {
-            Ordering::Less
-        } else if a.pos.y < b.pos.y {
-            Ordering::Greater
-        } else {
-            a.pos.x.cmp(&b.pos.x)
-        }
-    });
-
-    let mut iter = tiles.iter().peekable();
-
-    let rng = max_y - min_y;
-    for y in 0..=rng {
-        let y = max_y - y;
-        for x in min_x..=max_x {
-            if let Some(&next_tile) = iter.peek() {
-                let pos = Pos { x, y };
-                if next_tile.pos == pos {
-                    print!(
-                        "{}",
-                        match next_tile.tile_id {
-                            Type::Empty => " ",
-                            Type::Wall => "|",
-                            Type::Block => "=",
-                            Type::HorizontalPaddle => "-",
-                            Type::Ball => "*",
-                        }
-                    );
-                    iter.next();
-                }
-            } else {
-                print!(" ");
+        let result = run_loop(Prog::new(&mem_state), &mut FollowBall).unwrap();
+        assert_eq!(
+            GameResult {
+                score: 1,
+                inputs: 1
+            },
+            result
+        );
+
+        let mut scripted = Scripted::new(vec![Joystick::Left]);
+        let result = run_loop(Prog::new(&mem_state), &mut scripted).unwrap();
+        assert_eq!(-1, result.score);
+
+        assert!(matches!(
+            run_loop(Prog::new(&mem_state), &mut scripted),
+            Err(Error::NoAvailableInput)
+        ));
+    }
+
+    #[test]
+    fn save_states_rewind_and_branch() {
+        // Adds each joystick input to the score, forever.
+        let mem_state = vec![
+            3, 20, 1, 20, 21, 21, 104, -1, 104, 0, 4, 21, 1105, 1, 0, 0, 0, 0, 0, 0, 0, 0,
+        ];
+        let mut game = Game::new(Prog::new(&mem_state)).unwrap();
+        let mut states = SaveStates::new(2);
+
+        for _ in 0..3 {
+            states.push(&game);
+            game.input(Joystick::Right).unwrap();
+        }
+        assert_eq!(3, game.screen().score());
+        assert_eq!(2, states.len());
+
+        let mut game = states.rewind(1).unwrap();
+        assert_eq!(2, game.screen().score());
+        assert_eq!(2, game.inputs());
+
+        states.push(&game);
+        game.input(Joystick::Left).unwrap();
+        assert_eq!(1, game.screen().score());
+
+        // Rewinding past the oldest state stops at the oldest state.
+        let game = states.rewind(5).unwrap();
+        assert_eq!(1, game.inputs());
+        assert!(states.is_empty());
+        assert_eq!(None, states.rewind(1));
+    }
+
+    #[test]
+    fn input_after_game_over() {
+        let mut game = Game::new(Prog::new(&[104, 1, 104, 2, 104, 2, 99])).unwrap();
+        assert!(game.is_over());
+        assert!(matches!(
+            game.input(Joystick::Neutral),
+            Err(Error::GameOver)
+        ));
+        assert_eq!(0, game.inputs());
+    }
+
+    #[test]
+    fn rewind_recovers_missed_balls() {
+        use crate::controller::FollowBall;
+        use crate::intcode::parse_mem_state;
+
+        /// Never moves the paddle, so it misses the first ball which does not land on it.
+        struct Idle;
+
+        impl Controller for Idle {
+            fn joystick(&mut self, _screen: &Screen) -> Result<Joystick, Error> {
+                Ok(Joystick::Neutral)
             }
         }
-        println!();
+
+        let mut mem_state = parse_mem_state(include_str!("../input").trim()).unwrap();
+        mem_state[0] = 2;
+
+        let lost = run_loop(Prog::new(&mem_state), &mut Idle).unwrap();
+        assert_eq!(0, lost.score);
+
+        let won = run_loop(Prog::new(&mem_state), &mut FollowBall).unwrap();
+        let rewound = run_loop_with_rewind(Prog::new(&mem_state), &mut Idle, 64).unwrap();
+        assert_eq!(won.score, rewound.score);
+        assert!(rewound.score > 0);
+    }
+
+    #[test]
+    fn frame_per_input() {
+        use crate::controller::FollowBall;
+
+        let mem_state = vec![
+            104, 1, 104, 2, 104, 3, 104, 2, 104, 1, 104, 4, 3, 100, 104, -1, 104, 0, 4, 100, 99,
+        ];
+        let mut frames = Vec::new();
+        run_loop_with_frames(Prog::new(&mem_state), &mut FollowBall, |screen| {
+            frames.push(render(screen, &default_palette(), 3));
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(1, frames.len());
+        assert_eq!(6, frames[0].width());
+        assert_eq!(6, frames[0].height());
+        assert_eq!(Some(Rgb(255, 96, 64)), frames[0].get(3, 0));
+        assert_eq!(Some(Rgb(255, 255, 255)), frames[0].get(0, 5));
+        assert_eq!(Some(Rgb(0, 0, 0)), frames[0].get(0, 0));
+    }
+
+    #[test]
+    fn render_drawn_area() {
+        let mut screen = Screen::new();
+        screen.set(tile(0, 0, Type::Wall));
+        screen.set(tile(6, 0, Type::Wall));
+        screen.set(tile(0, 4, Type::HorizontalPaddle));
+        screen.set(tile(-1, 2, Type::Ball));
+
+        let image = render(&screen, &default_palette(), 2);
+        assert_eq!(16, image.width());
+        assert_eq!(10, image.height());
+        assert_eq!(Some(Rgb(255, 96, 64)), image.get(1, 5));
+        assert_eq!(Some(Rgb(128, 128, 128)), image.get(14, 0));
+        assert_eq!(Some(Rgb(255, 255, 255)), image.get(2, 9));
+        assert_eq!(Some(Rgb(0, 0, 0)), image.get(15, 9));
+        assert_eq!(None, image.get(16, 0));
+
+        let image = render(&Screen::new(), &default_palette(), 2);
+        assert_eq!((0, 0), (image.width(), image.height()));
+    }
+
+    #[test]
+    fn frame_dump_writes_numbered_frames() {
+        let dir = std::env::temp_dir().join(format!("aoc_2019_13_frames_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut screen = Screen::new();
+        screen.set(tile(0, 0, Type::Wall));
+        screen.set(tile(1, 0, Type::Ball));
+        let mut dump = FrameDump::new(&dir, Format::Ppm, default_palette(), 1);
+        dump.write_frame(&screen).unwrap();
+        screen.set(tile(1, 1, Type::Block));
+        dump.write_frame(&screen).unwrap();
+        assert_eq!(2, dump.frames());
+
+        let first = std::fs::read(dir.join("frame_00000.ppm")).unwrap();
+        let mut expected = b"P6\n2 1\n255\n".to_vec();
+        expected.extend_from_slice(&[128, 128, 128, 255, 96, 64]);
+        assert_eq!(expected, first);
+
+        let second = std::fs::read(dir.join("frame_00001.ppm")).unwrap();
+        assert!(second.starts_with(b"P6\n2 2\n255\n"));
+        assert_eq!(b"P6\n2 2\n255\n".len() + 2 * 2 * 3, second.len());
+        assert_eq!(&[64, 128, 255], &second[second.len() - 3..]);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn protocol_errors() {
+        let mut screen = Screen::new();
+        let mut output = ["1", "2", "3", "4"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<VecDeque<_>>();
+        assert!(matches!(
+            screen.update(&mut output),
+            Err(Error::Protocol(ProtocolError::TruncatedOutput {
+                expected: 3,
+                received: 1
+            }))
+        ));
+        assert_eq!(None, screen.bounds());
+
+        assert!(matches!(
+            draw_screen(Prog::new(&[104, 1, 104, 2, 104, 7, 99])),
+            Err(Error::Protocol(ProtocolError::UnknownTileId(id))) if id == "7"
+        ));
+        assert!(matches!(
+            draw_screen(Prog::new(&[104, 1, 104, 2, 104, 2, 3, 0, 99])),
+            Err(Error::Protocol(ProtocolError::UnexpectedInputRequest))
+        ));
+        let screen = draw_screen(Prog::new(&[104, 1, 104, 2, 104, 2, 99])).unwrap();
+        assert_eq!(1, screen.blocks_remaining());
+    }
+
+    #[test]
+    fn display_screen() {
+        let mut screen = Screen::new();
+        for x in 0..5 {
+            screen.set(tile(x, 0, Type::Wall));
+        }
+        screen.set(tile(1, 1, Type::Block));
+        screen.set(tile(3, 1, Type::Block));
+        screen.set(tile(2, 2, Type::Ball));
+        screen.set(tile(2, 3, Type::HorizontalPaddle));
+
+        assert_eq!("|||||\n = =\n  *\n  -\n", screen.to_string());
+    }
+
+    #[test]
+    fn display_smallest_y_first() {
+        let mut screen = Screen::new();
+        screen.set(tile(0, 7, Type::HorizontalPaddle));
+        screen.set(tile(0, -2, Type::Wall));
+        screen.set(tile(1, 3, Type::Ball));
+
+        let rows = screen.to_string();
+        let rows = rows.lines().collect::<Vec<_>>();
+        assert_eq!(10, rows.len());
+        assert_eq!("|", rows[0]);
+        assert_eq!(" *", rows[5]);
+        assert_eq!("-", rows[9]);
     }
 }
@@ -0,0 +1,98 @@
+use std::env;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+use aoc_2019_13::{
+    self,
+    arcade::{Game, SaveStates},
+    error::Error,
+    intcode::Prog,
+    play::{Key, Player},
+};
+
+/// The number of inputs which can be rewound.
+const SAVE_STATES: usize = 1000;
+
+/// Switches the terminal to raw mode, restoring the previous settings when dropped.
+struct RawMode {
+    saved: String,
+}
+
+impl RawMode {
+    fn enable() -> Result<Self, Error> {
+        let saved = stty(&["-g"])?;
+        stty(&["-icanon", "-echo", "min", "1"])?;
+        Ok(RawMode {
+            saved: saved.trim().to_string(),
+        })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = stty(&[&self.saved]);
+    }
+}
+
+fn stty(args: &[&str]) -> Result<String, Error> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()?;
+    if !output.status.success() {
+        return Err(Error::IoErr(io::Error::other("stty failed")));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn main() -> Result<(), Error> {
+    let path = env::args().nth(1).unwrap_or_else(|| String::from("input"));
+    let mut mem_state = aoc_2019_13::intcode::parse_mem_state(fs::read_to_string(path)?.trim())?;
+    mem_state[0] = 2;
+
+    let stdin = io::stdin();
+    let raw_mode = if stdin.is_terminal() {
+        Some(RawMode::enable()?)
+    } else {
+        None
+    };
+
+    let mut player = Player::new(stdin.lock(), io::stdout(), raw_mode.is_some());
+    let result = play(Prog::new(&mem_state), &mut player);
+    drop(raw_mode);
+
+    let game = result?;
+    if game.is_over() {
+        println!("Game over. Score: {}", game.screen().score());
+    } else {
+        println!(
+            "Quit. Score: {}  Blocks: {}",
+            game.screen().score(),
+            game.screen().blocks_remaining()
+        );
+    }
+    Ok(())
+}
+
+/// Plays until the game is over or the player quits, keeping a save state for each input.
+fn play<R: BufRead, W: Write>(prog: Prog, player: &mut Player<R, W>) -> Result<Game, Error> {
+    let mut states = SaveStates::new(SAVE_STATES);
+    let mut game = Game::new(prog)?;
+    while !game.is_over() {
+        match player.next_key(game.screen())? {
+            Key::Move(joystick) => {
+                states.push(&game);
+                game.input(joystick)?;
+            }
+            Key::Rewind(n) => {
+                if let Some(earlier) = states.rewind(n) {
+                    game = earlier;
+                }
+            }
+            // `next_key` handles pausing, so only quitting is left.
+            Key::Pause | Key::Quit => break,
+        }
+    }
+    Ok(game)
+}
@@ -0,0 +1,253 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::io::BufRead;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::arcade::{Joystick, Pos, Screen, Type};
+use crate::error::Error;
+
+/// Chooses a joystick input each time the game needs one.
+pub trait Controller {
+    fn joystick(&mut self, screen: &Screen) -> Result<Joystick, Error>;
+}
+
+impl<C: Controller + ?Sized> Controller for &mut C {
+    fn joystick(&mut self, screen: &Screen) -> Result<Joystick, Error> {
+        (**self).joystick(screen)
+    }
+}
+
+pub(crate) fn toward(from: i64, to: i64) -> Joystick {
+    if to > from {
+        Joystick::Right
+    } else if to < from {
+        Joystick::Left
+    } else {
+        Joystick::Neutral
+    }
+}
+
+/// Moves the paddle toward the ball's current column.
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub struct FollowBall;
+
+impl Controller for FollowBall {
+    fn joystick(&mut self, screen: &Screen) -> Result<Joystick, Error> {
+        match (screen.paddle(), screen.ball()) {
+            (Some(paddle), Some(ball)) => Ok(toward(paddle.x, ball.x)),
+            _ => Ok(Joystick::Neutral),
+        }
+    }
+}
+
+/// Moves the paddle toward the column where the ball is expected to reach the paddle's row.
+///
+/// The ball's direction is taken from its previous position, and a falling ball's trajectory
+/// is simulated by bouncing off walls. Otherwise the ball's current column is followed.
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub struct Predictive {
+    prev_ball: Option<Pos>,
+}
+
+impl Predictive {
+    #[must_use]
+    pub fn new() -> Self {
+        Predictive::default()
+    }
+
+    fn is_solid(screen: &Screen, x: i64, y: i64) -> bool {
+        matches!(
+            screen.get(Pos { x, y }),
+            Some(Type::Wall) | Some(Type::Block)
+        )
+    }
+
+    /// Simulates a falling ball until it reaches the row above the paddle.
+    ///
+    /// Returns `None` if the ball is rising or bounces back up, since blocks it hits on the
+    /// way may be destroyed.
+    fn landing_x(screen: &Screen, ball: Pos, prev: Pos, paddle: Pos) -> Option<i64> {
+        let mut dx = (ball.x - prev.x).signum();
+        let dy = ball.y - prev.y;
+        if dx == 0 || dy <= 0 {
+            return None;
+        }
+
+        let target_y = paddle.y - 1;
+        let mut x = ball.x;
+        for y in ball.y..target_y {
+            if Self::is_solid(screen, x, y + 1) {
+                return None;
+            }
+            if Self::is_solid(screen, x + dx, y) {
+                dx = -dx;
+            }
+            if Self::is_solid(screen, x + dx, y + 1) {
+                return None;
+            }
+            x += dx;
+        }
+        Some(x)
+    }
+}
+
+impl Controller for Predictive {
+    fn joystick(&mut self, screen: &Screen) -> Result<Joystick, Error> {
+        let (paddle, ball) = match (screen.paddle(), screen.ball()) {
+            (Some(paddle), Some(ball)) => (paddle, ball),
+            _ => return Ok(Joystick::Neutral),
+        };
+        let prev = self.prev_ball.replace(ball);
+
+        let target = prev
+            .and_then(|prev| Self::landing_x(screen, ball, prev, paddle))
+            .unwrap_or(ball.x);
+        Ok(toward(paddle.x, target))
+    }
+}
+
+/// Replays a fixed sequence of inputs.
+///
+/// Returns `Error::NoAvailableInput` once every input is used.
+#[derive(Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub struct Scripted {
+    inputs: VecDeque<Joystick>,
+}
+
+impl Scripted {
+    #[must_use]
+    pub fn new(inputs: Vec<Joystick>) -> Self {
+        Scripted {
+            inputs: inputs.into(),
+        }
+    }
+
+    /// Reads whitespace or comma separated inputs (`-1`, `0` or `1`) from a file.
+    pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        fs::read_to_string(path)?.parse()
+    }
+}
+
+impl FromStr for Scripted {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let inputs = s
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|t| !t.is_empty())
+            .map(Joystick::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Scripted::new(inputs))
+    }
+}
+
+impl Controller for Scripted {
+    fn joystick(&mut self, _screen: &Screen) -> Result<Joystick, Error> {
+        self.inputs.pop_front().ok_or(Error::NoAvailableInput)
+    }
+}
+
+/// Reads a line of input for each joystick input.
+///
+/// `a` moves left, `d` moves right and anything else leaves the joystick neutral.
+#[derive(Debug)]
+pub struct Keyboard<R> {
+    reader: R,
+}
+
+impl<R: BufRead> Keyboard<R> {
+    pub fn new(reader: R) -> Self {
+        Keyboard { reader }
+    }
+}
+
+impl<R: BufRead> Controller for Keyboard<R> {
+    fn joystick(&mut self, _screen: &Screen) -> Result<Joystick, Error> {
+        let mut line = String::new();
+        if self.reader.read_line(&mut line)? == 0 {
+            return Err(Error::NoAvailableInput);
+        }
+        Ok(match line.trim() {
+            "a" => Joystick::Left,
+            "d" => Joystick::Right,
+            _ => Joystick::Neutral,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arcade::Tile;
+
+    fn screen(rows: &[&str]) -> Screen {
+        let mut screen = Screen::new();
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                let tile_id = match c {
+                    '|' => Type::Wall,
+                    '=' => Type::Block,
+                    '-' => Type::HorizontalPaddle,
+                    '*' => Type::Ball,
+                    _ => Type::Empty,
+                };
+                screen.set(Tile {
+                    pos: Pos {
+                        x: x as i64,
+                        y: y as i64,
+                    },
+                    tile_id,
+                });
+            }
+        }
+        screen
+    }
+
+    #[test]
+    fn follow_ball() {
+        let screen = screen(&["|||||||", "|  *  |", "|     |", "|-    |"]);
+        assert_eq!(Joystick::Right, FollowBall.joystick(&screen).unwrap());
+    }
+
+    #[test]
+    fn predictive_bounces_off_walls() {
+        let mut predictive = Predictive::new();
+        let before = screen(&[
+            "|||||||", "|     |", "|   * |", "|     |", "|     |", "|     |", "|  -  |",
+        ]);
+        let after = screen(&[
+            "|||||||", "|     |", "|     |", "|    *|", "|     |", "|     |", "|  -  |",
+        ]);
+
+        // Without a previous position the ball is followed.
+        assert_eq!(Joystick::Right, predictive.joystick(&before).unwrap());
+        // Moving down and right from (5, 3), the ball bounces off the right wall and reaches
+        // the row above the paddle at x = 3.
+        assert_eq!(Joystick::Neutral, predictive.joystick(&after).unwrap());
+    }
+
+    #[test]
+    fn scripted_and_keyboard() {
+        let screen = Screen::new();
+
+        let mut scripted = "-1, 0\n1".parse::<Scripted>().unwrap();
+        assert_eq!(Joystick::Left, scripted.joystick(&screen).unwrap());
+        assert_eq!(Joystick::Neutral, scripted.joystick(&screen).unwrap());
+        assert_eq!(Joystick::Right, scripted.joystick(&screen).unwrap());
+        assert!(matches!(
+            scripted.joystick(&screen),
+            Err(Error::NoAvailableInput)
+        ));
+        assert!("2".parse::<Scripted>().is_err());
+
+        let mut keyboard = Keyboard::new("a\nd\n\n".as_bytes());
+        assert_eq!(Joystick::Left, keyboard.joystick(&screen).unwrap());
+        assert_eq!(Joystick::Right, keyboard.joystick(&screen).unwrap());
+        assert_eq!(Joystick::Neutral, keyboard.joystick(&screen).unwrap());
+        assert!(matches!(
+            keyboard.joystick(&screen),
+            Err(Error::NoAvailableInput)
+        ));
+    }
+}
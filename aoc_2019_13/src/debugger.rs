@@ -0,0 +1,269 @@
+use std::collections::{BTreeSet, VecDeque};
+
+use crate::error::Error;
+use crate::intcode::{Prog, ProgState, Undo};
+
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+struct Record {
+    undo: Undo,
+    input: Option<String>,
+    output: bool,
+}
+
+/// The instruction which last wrote to an address.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct WriteRecord {
+    /// The number of steps executed before the write.
+    pub step: usize,
+    /// The address of the instruction.
+    pub pc: usize,
+    /// The value at the address before the write.
+    pub prev: i64,
+}
+
+/// Steps a program forwards and backwards.
+///
+/// Every executed instruction is recorded so it can be undone, including the input it
+/// consumed and the output it produced.
+#[derive(Clone, Debug)]
+pub struct Debugger {
+    prog: Prog,
+    input: VecDeque<String>,
+    output: VecDeque<String>,
+    history: Vec<Record>,
+    breakpoints: BTreeSet<usize>,
+}
+
+impl Debugger {
+    #[must_use]
+    pub fn new(prog: Prog) -> Self {
+        Debugger {
+            prog,
+            input: VecDeque::new(),
+            output: VecDeque::new(),
+            history: Vec::new(),
+            breakpoints: BTreeSet::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn prog(&self) -> &Prog {
+        &self.prog
+    }
+
+    #[must_use]
+    pub fn output(&self) -> &VecDeque<String> {
+        &self.output
+    }
+
+    /// The number of steps which can be undone.
+    #[must_use]
+    pub fn steps(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn push_input(&mut self, input: String) {
+        self.input.push_back(input);
+    }
+
+    pub fn add_breakpoint(&mut self, pc: usize) {
+        self.breakpoints.insert(pc);
+    }
+
+    pub fn remove_breakpoint(&mut self, pc: usize) {
+        self.breakpoints.remove(&pc);
+    }
+
+    /// Executes a single instruction.
+    ///
+    /// Nothing is recorded if the program has halted or is waiting for input.
+    pub fn step(&mut self) -> Result<ProgState, Error> {
+        if self.prog.state() == ProgState::Halt {
+            return Ok(ProgState::Halt);
+        }
+
+        let next_input = self.input.front().cloned();
+        let input_len = self.input.len();
+        let output_len = self.output.len();
+
+        let undo = self
+            .prog
+            .step_with_undo(&mut self.input, &mut self.output)?;
+        if self.prog.state() == ProgState::NeedInput {
+            return Ok(ProgState::NeedInput);
+        }
+
+        self.history.push(Record {
+            undo,
+            input: if self.input.len() < input_len {
+                next_input
+            } else {
+                None
+            },
+            output: self.output.len() > output_len,
+        });
+        Ok(self.prog.state())
+    }
+
+    /// Executes instructions until a breakpoint is reached, the program halts or the
+    /// program needs input.
+    pub fn resume(&mut self) -> Result<ProgState, Error> {
+        loop {
+            let state = self.step()?;
+            if state != ProgState::Running || self.breakpoints.contains(&self.prog.pc()) {
+                return Ok(state);
+            }
+        }
+    }
+
+    /// Undoes the last executed instruction.
+    ///
+    /// Returns false if there are no instructions to undo.
+    pub fn reverse_step(&mut self) -> bool {
+        if let Some(record) = self.history.pop() {
+            self.prog.undo(record.undo);
+            if let Some(input) = record.input {
+                self.input.push_front(input);
+            }
+            if record.output {
+                self.output.pop_back();
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Undoes instructions until a breakpoint is reached.
+    ///
+    /// Returns false if the start of the program was reached without hitting a breakpoint.
+    pub fn reverse_continue(&mut self) -> bool {
+        while self.reverse_step() {
+            if self.breakpoints.contains(&self.prog.pc()) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Finds the instruction which last wrote to an address.
+    #[must_use]
+    pub fn last_write(&self, addr: usize) -> Option<WriteRecord> {
+        self.history
+            .iter()
+            .enumerate()
+            .rev()
+            .find_map(|(step, r)| match r.undo.write {
+                Some((index, prev)) if index == addr => Some(WriteRecord {
+                    step,
+                    pc: r.undo.pc,
+                    prev,
+                }),
+                _ => None,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reverse_restores_every_state() {
+        let mem_state = vec![
+            109, 1, 204, -1, 1001, 100, 1, 100, 1008, 100, 16, 101, 1006, 101, 0, 99,
+        ];
+        let mut debugger = Debugger::new(Prog::new(&mem_state));
+
+        let mut snapshots = vec![(debugger.prog().clone(), debugger.output().clone())];
+        while debugger.step().unwrap() != ProgState::Halt {
+            snapshots.push((debugger.prog().clone(), debugger.output().clone()));
+        }
+        assert_eq!(16, debugger.output().len());
+        assert_eq!(snapshots.len(), debugger.steps());
+
+        while let Some((prog, output)) = snapshots.pop() {
+            assert!(debugger.reverse_step());
+            assert_eq!(&prog, debugger.prog());
+            assert_eq!(&output, debugger.output());
+        }
+        assert!(!debugger.reverse_step());
+    }
+
+    #[test]
+    fn reverse_step_restores_input() {
+        let mem_state = vec![3, 5, 4, 5, 99, 0];
+        let mut debugger = Debugger::new(Prog::new(&mem_state));
+        debugger.push_input(String::from("42"));
+
+        assert_eq!(ProgState::Halt, debugger.resume().unwrap());
+        assert_eq!(&VecDeque::from(vec![String::from("42")]), debugger.output());
+
+        while debugger.reverse_step() {}
+        assert!(debugger.output().is_empty());
+        assert_eq!(ProgState::Halt, debugger.resume().unwrap());
+        assert_eq!(&VecDeque::from(vec![String::from("42")]), debugger.output());
+        assert_eq!(&[3, 5, 4, 5, 99, 42], debugger.prog().mem_state());
+    }
+
+    #[test]
+    fn need_input_is_not_recorded() {
+        let mem_state = vec![3, 3, 99, 0];
+        let mut debugger = Debugger::new(Prog::new(&mem_state));
+
+        assert_eq!(ProgState::NeedInput, debugger.step().unwrap());
+        assert_eq!(0, debugger.steps());
+
+        debugger.push_input(String::from("7"));
+        assert_eq!(ProgState::Halt, debugger.resume().unwrap());
+        assert_eq!(&[3, 3, 99, 7], debugger.prog().mem_state());
+    }
+
+    #[test]
+    fn breakpoints_and_last_write() {
+        let mem_state = vec![1101, 1, 2, 9, 1101, 3, 4, 9, 99, 0];
+        let mut debugger = Debugger::new(Prog::new(&mem_state));
+        debugger.add_breakpoint(4);
+
+        assert_eq!(ProgState::Running, debugger.resume().unwrap());
+        assert_eq!(4, debugger.prog().pc());
+        assert_eq!(ProgState::Halt, debugger.resume().unwrap());
+        assert_eq!(7, debugger.prog().mem_state()[9]);
+        assert_eq!(
+            Some(WriteRecord {
+                step: 1,
+                pc: 4,
+                prev: 3
+            }),
+            debugger.last_write(9)
+        );
+
+        assert!(debugger.reverse_continue());
+        assert_eq!(4, debugger.prog().pc());
+        assert_eq!(3, debugger.prog().mem_state()[9]);
+        assert_eq!(
+            Some(WriteRecord {
+                step: 0,
+                pc: 0,
+                prev: 0
+            }),
+            debugger.last_write(9)
+        );
+        assert_eq!(None, debugger.last_write(3));
+
+        assert!(!debugger.reverse_continue());
+        assert_eq!(0, debugger.prog().pc());
+        assert_eq!(ProgState::NotStarted, debugger.prog().state());
+    }
+
+    #[test]
+    fn undo_memory_growth() {
+        let mem_state = vec![1101, 1, 2, 20, 99];
+        let mut debugger = Debugger::new(Prog::new(&mem_state));
+
+        debugger.resume().unwrap();
+        assert_eq!(21, debugger.prog().mem_state().len());
+        while debugger.reverse_step() {}
+        assert_eq!(&Prog::new(&mem_state), debugger.prog());
+    }
+}
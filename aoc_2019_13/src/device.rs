@@ -0,0 +1,372 @@
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+use std::ops::Range;
+
+use crate::error::Error;
+
+/// A host device which is mapped into a range of program memory.
+///
+/// The offset is relative to the start of the mapped range.
+pub trait Device {
+    fn read(&mut self, offset: usize) -> Result<i64, Error>;
+    fn write(&mut self, offset: usize, value: i64) -> Result<(), Error>;
+}
+
+impl<D: Device + ?Sized> Device for &mut D {
+    fn read(&mut self, offset: usize) -> Result<i64, Error> {
+        (**self).read(offset)
+    }
+
+    fn write(&mut self, offset: usize, value: i64) -> Result<(), Error> {
+        (**self).write(offset, value)
+    }
+}
+
+struct Mapping<'a> {
+    addrs: Range<usize>,
+    device: Box<dyn Device + 'a>,
+}
+
+/// Intercepts memory reads and writes for the mapped address ranges.
+#[derive(Default)]
+pub struct DeviceBus<'a> {
+    mappings: Vec<Mapping<'a>>,
+}
+
+impl<'a> DeviceBus<'a> {
+    #[must_use]
+    pub fn new() -> Self {
+        DeviceBus {
+            mappings: Vec::new(),
+        }
+    }
+
+    /// Maps a device to a range of addresses.
+    pub fn map<D>(&mut self, addrs: Range<usize>, device: D) -> Result<(), Error>
+    where
+        D: Device + 'a,
+    {
+        if addrs.is_empty()
+            || self
+                .mappings
+                .iter()
+                .any(|m| m.addrs.start < addrs.end && addrs.start < m.addrs.end)
+        {
+            return Err(Error::InvalidDeviceMapping);
+        }
+
+        self.mappings.push(Mapping {
+            addrs,
+            device: Box::new(device),
+        });
+        Ok(())
+    }
+
+    fn find(&mut self, addr: usize) -> Option<&mut Mapping<'a>> {
+        self.mappings.iter_mut().find(|m| m.addrs.contains(&addr))
+    }
+
+    /// Reads from the device mapped at the address, if any.
+    pub(crate) fn read(&mut self, addr: usize) -> Result<Option<i64>, Error> {
+        match self.find(addr) {
+            Some(m) => {
+                let offset = addr - m.addrs.start;
+                m.device.read(offset).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Writes to the device mapped at the address, if any.
+    ///
+    /// Returns false if no device is mapped at the address.
+    pub(crate) fn write(&mut self, addr: usize, value: i64) -> Result<bool, Error> {
+        match self.find(addr) {
+            Some(m) => {
+                let offset = addr - m.addrs.start;
+                m.device.write(offset, value)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+}
+
+/// A grid of cells addressed in row-major order.
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub struct Framebuffer {
+    width: usize,
+    height: usize,
+    cells: Vec<i64>,
+}
+
+impl Framebuffer {
+    #[must_use]
+    pub fn new(width: usize, height: usize) -> Self {
+        Framebuffer {
+            width,
+            height,
+            cells: vec![0; width * height],
+        }
+    }
+
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The number of addresses which the framebuffer occupies.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    #[must_use]
+    pub fn get(&self, x: usize, y: usize) -> Option<i64> {
+        if x < self.width && y < self.height {
+            Some(self.cells[x + y * self.width])
+        } else {
+            None
+        }
+    }
+}
+
+impl Device for Framebuffer {
+    fn read(&mut self, offset: usize) -> Result<i64, Error> {
+        self.cells
+            .get(offset)
+            .copied()
+            .ok_or(Error::InvalidDeviceMapping)
+    }
+
+    fn write(&mut self, offset: usize, value: i64) -> Result<(), Error> {
+        let cell = self
+            .cells
+            .get_mut(offset)
+            .ok_or(Error::InvalidDeviceMapping)?;
+        *cell = value;
+        Ok(())
+    }
+}
+
+/// A clock which advances one tick every time it is read.
+///
+/// Writing sets the current tick.
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub struct Clock {
+    ticks: i64,
+}
+
+impl Clock {
+    #[must_use]
+    pub fn new(ticks: i64) -> Self {
+        Clock { ticks }
+    }
+
+    #[must_use]
+    pub fn ticks(&self) -> i64 {
+        self.ticks
+    }
+}
+
+impl Device for Clock {
+    fn read(&mut self, _offset: usize) -> Result<i64, Error> {
+        let ticks = self.ticks;
+        self.ticks += 1;
+        Ok(ticks)
+    }
+
+    fn write(&mut self, _offset: usize, value: i64) -> Result<(), Error> {
+        self.ticks = value;
+        Ok(())
+    }
+}
+
+/// A seeded pseudo-random source (xorshift64).
+///
+/// Writing reseeds the generator.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct RandomSource {
+    state: u64,
+}
+
+impl RandomSource {
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        RandomSource {
+            state: if seed == 0 {
+                0x9E37_79B9_7F4A_7C15
+            } else {
+                seed
+            },
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.state = x;
+        x
+    }
+}
+
+impl Device for RandomSource {
+    fn read(&mut self, _offset: usize) -> Result<i64, Error> {
+        Ok(i64::try_from(self.next_u64() >> 1)?)
+    }
+
+    fn write(&mut self, _offset: usize, value: i64) -> Result<(), Error> {
+        *self = RandomSource::new(u64::from_ne_bytes(value.to_ne_bytes()));
+        Ok(())
+    }
+}
+
+/// A character console.
+///
+/// Reads take the next queued character and writes append a character to the output.
+#[derive(Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub struct Console {
+    input: VecDeque<char>,
+    output: String,
+}
+
+impl Console {
+    #[must_use]
+    pub fn new(input: &str) -> Self {
+        Console {
+            input: input.chars().collect(),
+            output: String::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+}
+
+impl Device for Console {
+    fn read(&mut self, _offset: usize) -> Result<i64, Error> {
+        self.input
+            .pop_front()
+            .map(|c| i64::from(u32::from(c)))
+            .ok_or(Error::NoAvailableInput)
+    }
+
+    fn write(&mut self, _offset: usize, value: i64) -> Result<(), Error> {
+        let c = char::from_u32(u32::try_from(value)?).ok_or(Error::UnknownValue)?;
+        self.output.push(c);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intcode::{Prog, ProgState};
+    use crate::tests::*;
+
+    #[test]
+    fn overlapping_mapping() {
+        let mut bus = DeviceBus::new();
+        bus.map(10..20, Clock::default()).unwrap();
+        assert!(matches!(
+            bus.map(19..21, Clock::default()),
+            Err(Error::InvalidDeviceMapping)
+        ));
+        assert!(matches!(
+            bus.map(5..5, Clock::default()),
+            Err(Error::InvalidDeviceMapping)
+        ));
+        bus.map(20..21, Clock::default()).unwrap();
+    }
+
+    #[test]
+    fn framebuffer_writes() {
+        // Stores 7 at 101 and 8 at 102, then outputs the value at 102.
+        let mem_state = vec![1101, 3, 4, 101, 1101, 4, 4, 102, 4, 102, 99];
+        let mut framebuffer = Framebuffer::new(2, 2);
+        let mut test_output = TestOutput::new();
+
+        let mut prog = Prog::new(&mem_state);
+        let mut bus = DeviceBus::new();
+        bus.map(100..100 + framebuffer.len(), &mut framebuffer)
+            .unwrap();
+        prog.run_with_devices(&mut TestInput::new(vec![]), &mut test_output, &mut bus)
+            .unwrap();
+        drop(bus);
+
+        assert_eq!(ProgState::Halt, prog.state());
+        assert_eq!(vec!["8"], test_output.output);
+        assert_eq!(Some(0), framebuffer.get(0, 0));
+        assert_eq!(Some(7), framebuffer.get(1, 0));
+        assert_eq!(Some(8), framebuffer.get(0, 1));
+        assert_eq!(Some(0), framebuffer.get(1, 1));
+        assert_eq!(mem_state, prog.mem_state());
+    }
+
+    #[test]
+    fn clock_reads() {
+        // Outputs the clock twice.
+        let mem_state = vec![4, 50, 4, 50, 99];
+        let mut test_output = TestOutput::new();
+
+        let mut prog = Prog::new(&mem_state);
+        let mut bus = DeviceBus::new();
+        bus.map(50..51, Clock::new(40)).unwrap();
+        prog.run_with_devices(&mut TestInput::new(vec![]), &mut test_output, &mut bus)
+            .unwrap();
+
+        assert_eq!(vec!["40", "41"], test_output.output);
+    }
+
+    #[test]
+    fn random_is_deterministic() {
+        let mem_state = vec![4, 50, 4, 50, 99];
+
+        let run = |seed| {
+            let mut test_output = TestOutput::new();
+            let mut prog = Prog::new(&mem_state);
+            let mut bus = DeviceBus::new();
+            bus.map(50..51, RandomSource::new(seed)).unwrap();
+            prog.run_with_devices(&mut TestInput::new(vec![]), &mut test_output, &mut bus)
+                .unwrap();
+            test_output.output
+        };
+
+        assert_eq!(run(1), run(1));
+        assert_ne!(run(1), run(2));
+    }
+
+    #[test]
+    fn console_echo() {
+        // Copies two characters from the console back to the console.
+        let mem_state = vec![1001, 60, 0, 60, 1001, 60, 0, 60, 99];
+        let mut console = Console::new("hi");
+
+        let mut prog = Prog::new(&mem_state);
+        let mut bus = DeviceBus::new();
+        bus.map(60..61, &mut console).unwrap();
+        prog.run_with_devices(
+            &mut TestInput::new(vec![]),
+            &mut TestOutput::new(),
+            &mut bus,
+        )
+        .unwrap();
+        drop(bus);
+
+        assert_eq!("hi", console.output());
+    }
+}
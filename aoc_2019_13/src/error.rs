@@ -4,6 +4,44 @@ use std::{
     io, num,
 };
 
+/// A program which does not follow the protocol a robot or game expects.
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub enum ProtocolError {
+    /// Fewer values were output than a message needs.
+    TruncatedOutput {
+        expected: usize,
+        received: usize,
+    },
+    /// More values were output than expected before the program waited for input or halted.
+    ExtraOutput(usize),
+    UnknownTileId(String),
+    UnknownColor(String),
+    UnknownTurn(String),
+    /// The program waited for input when it should have halted.
+    UnexpectedInputRequest,
+    /// The program halted without reading its input.
+    UnusedInput,
+}
+
+impl Display for ProtocolError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProtocolError::TruncatedOutput { expected, received } => write!(
+                f,
+                "truncated output: expected {expected} values, received {received}"
+            ),
+            ProtocolError::ExtraOutput(n) => write!(f, "{n} unexpected output values"),
+            ProtocolError::UnknownTileId(v) => write!(f, "unknown tile id: {v}"),
+            ProtocolError::UnknownColor(v) => write!(f, "unknown color: {v}"),
+            ProtocolError::UnknownTurn(v) => write!(f, "unknown turn: {v}"),
+            ProtocolError::UnexpectedInputRequest => {
+                f.write_str("waiting for input when the program should have halted")
+            }
+            ProtocolError::UnusedInput => f.write_str("halted without reading input"),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum Error {
     IoErr(io::Error),
@@ -11,6 +49,11 @@ pub enum Error {
     TryFromIntError(num::TryFromIntError),
     NoAvailableInput,
     UnknownValue,
+    InvalidDeviceMapping,
+    /// Input was given to a game which has halted.
+    GameOver,
+    Protocol(ProtocolError),
+    Ocr(aoc_ocr::error::Error),
 }
 
 impl error::Error for Error {}
@@ -23,6 +66,10 @@ impl Display for Error {
             Error::TryFromIntError(e) => Display::fmt(e, f),
             Error::NoAvailableInput => f.write_str("no available input"),
             Error::UnknownValue => f.write_str("unknown value"),
+            Error::InvalidDeviceMapping => f.write_str("invalid device mapping"),
+            Error::GameOver => f.write_str("game is over"),
+            Error::Protocol(e) => Display::fmt(e, f),
+            Error::Ocr(e) => Display::fmt(e, f),
         }
     }
 }
@@ -44,3 +91,15 @@ impl From<num::TryFromIntError> for Error {
         Error::TryFromIntError(other)
     }
 }
+
+impl From<ProtocolError> for Error {
+    fn from(other: ProtocolError) -> Self {
+        Error::Protocol(other)
+    }
+}
+
+impl From<aoc_ocr::error::Error> for Error {
+    fn from(other: aoc_ocr::error::Error) -> Self {
+        Error::Ocr(other)
+    }
+}
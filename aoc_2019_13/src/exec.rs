@@ -0,0 +1,328 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Wake, Waker};
+
+use crate::error::Error;
+use crate::intcode::{Prog, ProgState};
+
+struct Shared<T> {
+    queue: VecDeque<T>,
+    senders: usize,
+    waker: Option<Waker>,
+}
+
+impl<T> Shared<T> {
+    fn wake(&mut self) {
+        if let Some(waker) = self.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// Creates an unbounded channel for tasks on the same thread.
+#[must_use]
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let shared = Rc::new(RefCell::new(Shared {
+        queue: VecDeque::new(),
+        senders: 1,
+        waker: None,
+    }));
+    (Sender(Rc::clone(&shared)), Receiver(shared))
+}
+
+/// Sends values to a `Receiver`.
+pub struct Sender<T>(Rc<RefCell<Shared<T>>>);
+
+impl<T> Sender<T> {
+    pub fn send(&self, value: T) {
+        let mut shared = self.0.borrow_mut();
+        shared.queue.push_back(value);
+        shared.wake();
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.0.borrow_mut().senders += 1;
+        Sender(Rc::clone(&self.0))
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut shared = self.0.borrow_mut();
+        shared.senders -= 1;
+        if shared.senders == 0 {
+            shared.wake();
+        }
+    }
+}
+
+/// Receives values sent by a `Sender`.
+pub struct Receiver<T>(Rc<RefCell<Shared<T>>>);
+
+impl<T> Receiver<T> {
+    /// Waits for the next value.
+    ///
+    /// Resolves to `None` once every sender is dropped and no values remain.
+    pub fn recv(&mut self) -> Recv<'_, T> {
+        Recv { receiver: self }
+    }
+
+    pub fn try_recv(&mut self) -> Option<T> {
+        self.0.borrow_mut().queue.pop_front()
+    }
+}
+
+/// The future returned by `Receiver::recv`.
+pub struct Recv<'a, T> {
+    receiver: &'a mut Receiver<T>,
+}
+
+impl<T> Future for Recv<'_, T> {
+    type Output = Option<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut shared = self.receiver.0.borrow_mut();
+        if let Some(value) = shared.queue.pop_front() {
+            Poll::Ready(Some(value))
+        } else if shared.senders == 0 {
+            Poll::Ready(None)
+        } else {
+            shared.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl Prog {
+    /// Runs the program, awaiting input from a channel and sending output to a channel.
+    ///
+    /// Returns `Error::NoAvailableInput` if the program needs input after every input sender
+    /// is dropped.
+    pub async fn run_async(
+        &mut self,
+        input: &mut Receiver<i64>,
+        output: &Sender<i64>,
+    ) -> Result<(), Error> {
+        let mut prog_input = VecDeque::<String>::new();
+        let mut prog_output = VecDeque::<String>::new();
+
+        loop {
+            self.run(&mut prog_input, &mut prog_output)?;
+            for o in prog_output.drain(..) {
+                output.send(o.parse::<i64>()?);
+            }
+
+            match self.state() {
+                ProgState::Halt => return Ok(()),
+                ProgState::NeedInput => match input.recv().await {
+                    Some(value) => prog_input.push_back(value.to_string()),
+                    None => return Err(Error::NoAvailableInput),
+                },
+                ProgState::NotStarted | ProgState::Running => unreachable!(),
+            }
+        }
+    }
+}
+
+struct JoinState<T> {
+    value: Option<T>,
+    waker: Option<Waker>,
+}
+
+/// The eventual output of a spawned task.
+///
+/// Can be awaited by another task or checked after the executor runs.
+pub struct JoinHandle<T>(Rc<RefCell<JoinState<T>>>);
+
+impl<T> JoinHandle<T> {
+    /// Takes the output if the task has completed.
+    pub fn try_take(&self) -> Option<T> {
+        self.0.borrow_mut().value.take()
+    }
+}
+
+impl<T> Future for JoinHandle<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.0.borrow_mut();
+        if let Some(value) = state.value.take() {
+            Poll::Ready(value)
+        } else {
+            state.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+struct TaskWaker {
+    id: usize,
+    ready: Arc<Mutex<VecDeque<usize>>>,
+}
+
+impl Wake for TaskWaker {
+    fn wake(self: Arc<Self>) {
+        self.ready.lock().unwrap().push_back(self.id);
+    }
+}
+
+type Task<'a> = Pin<Box<dyn Future<Output = ()> + 'a>>;
+
+/// Runs tasks cooperatively on the current thread.
+#[derive(Default)]
+pub struct LocalExecutor<'a> {
+    tasks: Vec<Option<Task<'a>>>,
+    ready: Arc<Mutex<VecDeque<usize>>>,
+}
+
+impl<'a> LocalExecutor<'a> {
+    #[must_use]
+    pub fn new() -> Self {
+        LocalExecutor {
+            tasks: Vec::new(),
+            ready: Arc::new(Mutex::new(VecDeque::new())),
+        }
+    }
+
+    pub fn spawn<F>(&mut self, future: F) -> JoinHandle<F::Output>
+    where
+        F: Future + 'a,
+    {
+        let state = Rc::new(RefCell::new(JoinState {
+            value: None,
+            waker: None,
+        }));
+        let task_state = Rc::clone(&state);
+        let task = async move {
+            let value = future.await;
+            let mut state = task_state.borrow_mut();
+            state.value = Some(value);
+            if let Some(waker) = state.waker.take() {
+                waker.wake();
+            }
+        };
+
+        let id = self.tasks.len();
+        self.tasks.push(Some(Box::pin(task)));
+        self.ready.lock().unwrap().push_back(id);
+        JoinHandle(state)
+    }
+
+    /// Polls tasks until every task completes or none of them can make progress.
+    ///
+    /// Returns the number of tasks which have not completed.
+    pub fn run(&mut self) -> usize {
+        loop {
+            let next = self.ready.lock().unwrap().pop_front();
+            let id = match next {
+                Some(id) => id,
+                None => break,
+            };
+
+            if let Some(task) = self.tasks[id].as_mut() {
+                let waker = Waker::from(Arc::new(TaskWaker {
+                    id,
+                    ready: Arc::clone(&self.ready),
+                }));
+                let mut cx = Context::from_waker(&waker);
+                if task.as_mut().poll(&mut cx).is_ready() {
+                    self.tasks[id] = None;
+                }
+            }
+        }
+
+        self.tasks.iter().filter(|t| t.is_some()).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn channel_closes_when_senders_drop() {
+        let mut executor = LocalExecutor::new();
+        let (tx, mut rx) = channel::<i64>();
+        let tx2 = tx.clone();
+
+        let sum = executor.spawn(async move {
+            let mut sum = 0;
+            while let Some(v) = rx.recv().await {
+                sum += v;
+            }
+            sum
+        });
+        executor.spawn(async move {
+            tx.send(1);
+            tx2.send(2);
+        });
+
+        assert_eq!(0, executor.run());
+        assert_eq!(Some(3), sum.try_take());
+    }
+
+    #[test]
+    fn stalled_tasks_are_reported() {
+        let mut executor = LocalExecutor::new();
+        let (_tx, mut rx) = channel::<i64>();
+
+        let handle = executor.spawn(async move { rx.recv().await });
+
+        assert_eq!(1, executor.run());
+        assert_eq!(None, handle.try_take());
+    }
+
+    #[test]
+    fn prog_with_host_task() {
+        // Outputs double the input until the input is 0.
+        let mem_state = vec![
+            3, 15, 1006, 15, 14, 1002, 15, 2, 15, 4, 15, 1105, 1, 0, 99, 0,
+        ];
+        let mut executor = LocalExecutor::new();
+        let (to_prog, mut prog_input) = channel();
+        let (prog_output, mut from_prog) = channel();
+
+        let prog = executor.spawn(async move {
+            let mut prog = Prog::new(&mem_state);
+            prog.run_async(&mut prog_input, &prog_output).await
+        });
+        let host = executor.spawn(async move {
+            let mut received = Vec::new();
+            for v in 1..=3 {
+                to_prog.send(v);
+                received.push(from_prog.recv().await.unwrap());
+            }
+            to_prog.send(0);
+            received
+        });
+
+        assert_eq!(0, executor.run());
+        assert!(prog.try_take().unwrap().is_ok());
+        assert_eq!(Some(vec![2, 4, 6]), host.try_take());
+    }
+
+    #[test]
+    fn prog_input_closed() {
+        let mut executor = LocalExecutor::new();
+        let (to_prog, mut prog_input) = channel();
+        let (prog_output, _from_prog) = channel();
+        drop(to_prog);
+
+        let prog = executor.spawn(async move {
+            let mut prog = Prog::new(&[3, 0, 99]);
+            prog.run_async(&mut prog_input, &prog_output).await
+        });
+
+        assert_eq!(0, executor.run());
+        assert!(matches!(
+            prog.try_take(),
+            Some(Err(Error::NoAvailableInput))
+        ));
+    }
+}
@@ -1,8 +1,8 @@
-use std::cmp::Ordering;
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::convert::TryFrom;
 
-use crate::error::Error;
+use crate::error::{Error, ProtocolError};
+use crate::image::{Image, Palette, Rgb};
 use crate::intcode::{Prog, ProgState};
 
 #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
@@ -20,7 +20,7 @@ impl TryFrom<String> for Color {
         } else if other == "1" {
             Ok(Color::White)
         } else {
-            Err(Error::UnknownValue)
+            Err(ProtocolError::UnknownColor(other).into())
         }
     }
 }
@@ -40,13 +40,13 @@ impl TryFrom<String> for TurnDir {
         } else if other == "1" {
             Ok(TurnDir::Right)
         } else {
-            Err(Error::UnknownValue)
+            Err(ProtocolError::UnknownTurn(other).into())
         }
     }
 }
 
 #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
-enum RobotDir {
+pub enum RobotDir {
     Up,
     Down,
     Left,
@@ -70,36 +70,169 @@ impl RobotDir {
 
 #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
 pub struct Pos {
-    x: isize,
-    y: isize,
+    pub x: isize,
+    pub y: isize,
 }
 
-#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
 pub struct Panel {
-    pos: Pos,
-    color: Color,
+    pub pos: Pos,
+    pub color: Color,
+}
+
+/// The painted panels of a hull, keyed by position.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Panels {
+    colors: HashMap<Pos, Color>,
+    bounds: Option<(Pos, Pos)>,
+    white: usize,
+}
+
+impl Panels {
+    #[must_use]
+    pub fn new() -> Self {
+        Panels::default()
+    }
+
+    /// The color of a panel, or `None` if it has never been painted.
+    #[must_use]
+    pub fn get(&self, pos: Pos) -> Option<Color> {
+        self.colors.get(&pos).copied()
+    }
+
+    pub fn paint(&mut self, pos: Pos, color: Color) {
+        if let Some(prev) = self.colors.insert(pos, color) {
+            if prev == Color::White {
+                self.white -= 1;
+            }
+        }
+        if color == Color::White {
+            self.white += 1;
+        }
+
+        self.bounds = Some(match self.bounds {
+            Some((min, max)) => (
+                Pos {
+                    x: min.x.min(pos.x),
+                    y: min.y.min(pos.y),
+                },
+                Pos {
+                    x: max.x.max(pos.x),
+                    y: max.y.max(pos.y),
+                },
+            ),
+            None => (pos, pos),
+        });
+    }
+
+    /// The minimum and maximum painted positions (inclusive).
+    #[must_use]
+    pub fn bounds(&self) -> Option<(Pos, Pos)> {
+        self.bounds
+    }
+
+    /// The number of panels painted at least once.
+    #[must_use]
+    pub fn painted(&self) -> usize {
+        self.colors.len()
+    }
+
+    /// The number of painted panels which are currently a color.
+    #[must_use]
+    pub fn count(&self, color: Color) -> usize {
+        match color {
+            Color::White => self.white,
+            Color::Black => self.colors.len() - self.white,
+        }
+    }
+
+    /// Every painted panel in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = Panel> + '_ {
+        self.colors
+            .iter()
+            .map(|(&pos, &color)| Panel { pos, color })
+    }
 }
 
-pub fn paint_hull(mut prog: Prog) -> Result<Vec<Panel>, Error> {
-    let mut robot_dir = RobotDir::Up;
+/// How the robot starts and what it records.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RobotConfig {
+    /// Panels which are already painted before the robot starts.
+    pub start_panels: Panels,
+    /// The color of panels which have never been painted.
+    pub default_color: Color,
+    pub heading: RobotDir,
+    /// Treat a turn output of `0` as right and `1` as left.
+    pub swap_turns: bool,
+    /// Record the robot's position, the color painted and the new heading for every step.
+    pub record_history: bool,
+}
+
+impl Default for RobotConfig {
+    fn default() -> Self {
+        RobotConfig {
+            start_panels: Panels::new(),
+            default_color: Color::Black,
+            heading: RobotDir::Up,
+            swap_turns: false,
+            record_history: false,
+        }
+    }
+}
+
+impl RobotConfig {
+    /// Every panel starts black.
+    #[must_use]
+    pub fn part1() -> Self {
+        RobotConfig::default()
+    }
+
+    /// The starting panel is white.
+    #[must_use]
+    pub fn part2() -> Self {
+        let mut config = RobotConfig::default();
+        config.start_panels.paint(Pos { x: 0, y: 0 }, Color::White);
+        config
+    }
+}
+
+/// A single step of the robot.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct Step {
+    /// The position painted.
+    pub pos: Pos,
+    pub color: Color,
+    /// The heading after turning.
+    pub heading: RobotDir,
+}
+
+/// The panels painted by the robot, not including the starting panels.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Painting {
+    pub panels: Panels,
+    /// Every step, if requested in the config.
+    pub history: Vec<Step>,
+}
+
+/// Paints the hull starting on a white panel.
+pub fn paint_hull(prog: Prog) -> Result<Panels, Error> {
+    Ok(paint_hull_with(prog, &RobotConfig::part2())?.panels)
+}
+
+pub fn paint_hull_with(mut prog: Prog, config: &RobotConfig) -> Result<Painting, Error> {
+    let mut robot_dir = config.heading;
     let mut robot_pos = Pos { x: 0, y: 0 };
-    let mut panels = Vec::<Panel>::new();
+    let mut painting = Painting::default();
 
     let mut input = VecDeque::<String>::new();
     let mut output = VecDeque::<String>::new();
 
     loop {
-        let index = panels.iter().position(|p| p.pos == robot_pos);
-        let color = if let Some(index) = index {
-            panels[index].color
-        } else {
-            let start_pos = Pos { x: 0, y: 0 };
-            if robot_pos == start_pos {
-                Color::White
-            } else {
-                Color::Black
-            }
-        };
+        let color = painting
+            .panels
+            .get(robot_pos)
+            .or_else(|| config.start_panels.get(robot_pos))
+            .unwrap_or(config.default_color);
 
         input.push_back(match color {
             Color::Black => "0".to_string(),
@@ -107,35 +240,44 @@ pub fn paint_hull(mut prog: Prog) -> Result<Vec<Panel>, Error> {
         });
         prog.run(&mut input, &mut output)?;
 
-        let panel = if let Some(index) = index {
-            panels.get_mut(index).unwrap()
-        } else {
-            let panel = Panel {
-                pos: robot_pos,
-                color: Color::Black,
-            };
-            panels.push(panel);
-            panels.last_mut().unwrap()
-        };
-
-        if let Some(v) = output.pop_front() {
-            let color = Color::try_from(v)?;
-            panel.color = color;
-        } else {
-            panic!("unexpected program state");
+        if prog.state() == ProgState::Halt && !input.is_empty() {
+            return Err(ProtocolError::UnusedInput.into());
         }
-
-        if let Some(v) = output.pop_front() {
-            let turn_dir = TurnDir::try_from(v)?;
-            robot_dir = robot_dir.turn(turn_dir);
-            match robot_dir {
-                RobotDir::Up => robot_pos.y += 1,
-                RobotDir::Left => robot_pos.x -= 1,
-                RobotDir::Down => robot_pos.y -= 1,
-                RobotDir::Right => robot_pos.x += 1,
+        if output.len() != 2 {
+            return Err(if output.len() < 2 {
+                ProtocolError::TruncatedOutput {
+                    expected: 2,
+                    received: output.len(),
+                }
+            } else {
+                ProtocolError::ExtraOutput(output.len() - 2)
             }
-        } else {
-            panic!("unexpected program state");
+            .into());
+        }
+
+        let color = Color::try_from(output.pop_front().expect("color output"))?;
+        painting.panels.paint(robot_pos, color);
+
+        let mut turn_dir = TurnDir::try_from(output.pop_front().expect("turn output"))?;
+        if config.swap_turns {
+            turn_dir = match turn_dir {
+                TurnDir::Left => TurnDir::Right,
+                TurnDir::Right => TurnDir::Left,
+            };
+        }
+        robot_dir = robot_dir.turn(turn_dir);
+        if config.record_history {
+            painting.history.push(Step {
+                pos: robot_pos,
+                color,
+                heading: robot_dir,
+            });
+        }
+        match robot_dir {
+            RobotDir::Up => robot_pos.y += 1,
+            RobotDir::Left => robot_pos.x -= 1,
+            RobotDir::Down => robot_pos.y -= 1,
+            RobotDir::Right => robot_pos.x += 1,
         }
 
         match prog.state() {
@@ -143,45 +285,226 @@ pub fn paint_hull(mut prog: Prog) -> Result<Vec<Panel>, Error> {
                 break;
             }
             ProgState::NeedInput => {}
-            ProgState::NotStarted => unreachable!(),
+            ProgState::NotStarted | ProgState::Running => unreachable!(),
         }
     }
 
-    assert!(input.is_empty());
-    assert!(output.is_empty());
-
-    Ok(panels)
+    Ok(painting)
 }
 
-pub fn display_panels(mut panels: Vec<Panel>) {
-    let max_x = panels.iter().map(|p| p.pos.x).max().unwrap();
-    let min_x = panels.iter().map(|p| p.pos.x).min().unwrap();
-    let max_y = panels.iter().map(|p| p.pos.y).max().unwrap();
-    let min_y = panels.iter().map(|p| p.pos.y).min().unwrap();
+pub fn display_panels(panels: &Panels) {
+    let (min, max) = match panels.bounds() {
+        Some(bounds) => bounds,
+        None => return,
+    };
 
-    panels.sort_by(|a, b| {
-        if a.pos.y > b.pos.y {
-            Ordering::Less
-        } else if a.pos.y < b.pos.y {
-            Ordering::Greater
-        } else {
-            a.pos.x.cmp(&b.pos.x)
-        }
-    });
-
-    let rng = max_y - min_y;
-    for y in 0..=rng {
-        let y = max_y - y;
-        for x in min_x..=max_x {
-            if let Some(panel) = panels.iter().find(|p| p.pos == Pos { x, y }) {
-                match panel.color {
-                    Color::Black => print!(" "),
-                    Color::White => print!("*"),
-                }
-            } else {
-                print!(" ");
+    for y in (min.y..=max.y).rev() {
+        for x in min.x..=max.x {
+            match panels.get(Pos { x, y }) {
+                Some(Color::White) => print!("*"),
+                Some(Color::Black) | None => print!(" "),
             }
         }
         println!();
     }
 }
+
+/// Reads the letters painted in white, with the largest `y` at the top.
+pub fn read_panels(panels: &Panels) -> Result<String, Error> {
+    let (min, max) = match panels.bounds() {
+        Some(bounds) => bounds,
+        None => return Ok(String::new()),
+    };
+
+    let width = usize::try_from(max.x - min.x + 1)?;
+    let pixels = (min.y..=max.y)
+        .rev()
+        .flat_map(|y| (min.x..=max.x).map(move |x| Pos { x, y }))
+        .map(|pos| panels.get(pos) == Some(Color::White))
+        .collect::<Vec<_>>();
+    Ok(aoc_ocr::recognize(&pixels, width)?)
+}
+
+/// Black panels on a dark grey hull, with white panels in white.
+#[must_use]
+pub fn default_palette() -> Palette<Color> {
+    Palette::new(Rgb(32, 32, 32))
+        .with(Color::Black, Rgb(0, 0, 0))
+        .with(Color::White, Rgb(255, 255, 255))
+}
+
+/// Renders the panels as an image with the largest `y` at the top, drawing each panel as a
+/// `scale` by `scale` square.
+#[must_use]
+pub fn render_panels(panels: &Panels, palette: &Palette<Color>, scale: usize) -> Image {
+    let (min, max) = panels
+        .bounds()
+        .unwrap_or((Pos { x: 0, y: 0 }, Pos { x: 0, y: 0 }));
+
+    Image::from_cells(
+        usize::try_from(max.x - min.x + 1).expect("width to fit in usize"),
+        usize::try_from(max.y - min.y + 1).expect("height to fit in usize"),
+        scale,
+        |x, y| {
+            palette.color(
+                panels
+                    .get(Pos {
+                        x: min.x + isize::try_from(x).expect("column to fit in isize"),
+                        y: max.y - isize::try_from(y).expect("row to fit in isize"),
+                    })
+                    .as_ref(),
+            )
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn panels_track_bounds_and_counts() {
+        let mut panels = Panels::new();
+        assert_eq!(None, panels.bounds());
+
+        panels.paint(Pos { x: 0, y: 0 }, Color::White);
+        panels.paint(Pos { x: -3, y: 2 }, Color::Black);
+        panels.paint(Pos { x: 4, y: -1 }, Color::White);
+        panels.paint(Pos { x: 0, y: 0 }, Color::Black);
+
+        assert_eq!(3, panels.painted());
+        assert_eq!(1, panels.count(Color::White));
+        assert_eq!(2, panels.count(Color::Black));
+        assert_eq!(Some(Color::Black), panels.get(Pos { x: 0, y: 0 }));
+        assert_eq!(None, panels.get(Pos { x: 1, y: 0 }));
+        assert_eq!(
+            Some((Pos { x: -3, y: -1 }, Pos { x: 4, y: 2 })),
+            panels.bounds()
+        );
+        assert_eq!(3, panels.iter().count());
+    }
+
+    /// Outputs the color it sees inverted, then turns left, for each of four steps.
+    const INVERT_AND_TURN: &[i64] = &[
+        3, 100, 1002, 100, -1, 100, 1001, 100, 1, 100, 4, 100, 104, 0, 1001, 101, 1, 101, 1007,
+        101, 4, 102, 1005, 102, 0, 99,
+    ];
+
+    #[test]
+    fn config_start_and_history() {
+        let mut config = RobotConfig::part2();
+        config.start_panels.paint(Pos { x: -1, y: 0 }, Color::White);
+        config.record_history = true;
+
+        let painting = paint_hull_with(Prog::new(INVERT_AND_TURN), &config).unwrap();
+        assert_eq!(
+            vec![
+                Step {
+                    pos: Pos { x: 0, y: 0 },
+                    color: Color::Black,
+                    heading: RobotDir::Left,
+                },
+                Step {
+                    pos: Pos { x: -1, y: 0 },
+                    color: Color::Black,
+                    heading: RobotDir::Down,
+                },
+                Step {
+                    pos: Pos { x: -1, y: -1 },
+                    color: Color::White,
+                    heading: RobotDir::Right,
+                },
+                Step {
+                    pos: Pos { x: 0, y: -1 },
+                    color: Color::White,
+                    heading: RobotDir::Up,
+                },
+            ],
+            painting.history
+        );
+        assert_eq!(4, painting.panels.painted());
+        assert_eq!(2, painting.panels.count(Color::White));
+
+        let mut config = RobotConfig::part1();
+        config.heading = RobotDir::Right;
+        config.swap_turns = true;
+        let painting = paint_hull_with(Prog::new(INVERT_AND_TURN), &config).unwrap();
+        assert!(painting.history.is_empty());
+        assert_eq!(
+            Some((Pos { x: -1, y: -1 }, Pos { x: 0, y: 0 })),
+            painting.panels.bounds()
+        );
+        assert_eq!(4, painting.panels.count(Color::White));
+    }
+
+    #[test]
+    fn read_letters() {
+        let art = [
+            ".##..####.",
+            "#..#.#....",
+            "#..#.###..",
+            "####.#....",
+            "#..#.#....",
+            "#..#.####.",
+        ];
+        let mut panels = Panels::new();
+        for (y, row) in art.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                let color = if c == '#' { Color::White } else { Color::Black };
+                let pos = Pos {
+                    x: isize::try_from(x).unwrap(),
+                    y: -isize::try_from(y).unwrap(),
+                };
+                panels.paint(pos, color);
+            }
+        }
+        assert_eq!("AE", read_panels(&panels).unwrap());
+        assert_eq!("", read_panels(&Panels::new()).unwrap());
+    }
+
+    #[test]
+    fn render_with_largest_y_at_top() {
+        let mut panels = Panels::new();
+        panels.paint(Pos { x: -1, y: 2 }, Color::White);
+        panels.paint(Pos { x: 1, y: 0 }, Color::Black);
+
+        let image = render_panels(&panels, &default_palette(), 3);
+        assert_eq!(9, image.width());
+        assert_eq!(9, image.height());
+        assert_eq!(Some(Rgb(255, 255, 255)), image.get(0, 0));
+        assert_eq!(Some(Rgb(255, 255, 255)), image.get(2, 2));
+        assert_eq!(Some(Rgb(0, 0, 0)), image.get(8, 8));
+        assert_eq!(Some(Rgb(32, 32, 32)), image.get(4, 4));
+        assert_eq!(None, image.get(9, 0));
+    }
+
+    #[test]
+    fn protocol_errors() {
+        let config = RobotConfig::part1();
+        let paint = |mem_state: &[i64]| paint_hull_with(Prog::new(mem_state), &config);
+
+        assert!(matches!(
+            paint(&[3, 0, 104, 1, 99]),
+            Err(Error::Protocol(ProtocolError::TruncatedOutput {
+                expected: 2,
+                received: 1
+            }))
+        ));
+        assert!(matches!(
+            paint(&[3, 0, 104, 1, 104, 0, 104, 0, 99]),
+            Err(Error::Protocol(ProtocolError::ExtraOutput(1)))
+        ));
+        assert!(matches!(
+            paint(&[3, 0, 104, 1, 104, 2, 99]),
+            Err(Error::Protocol(ProtocolError::UnknownTurn(v))) if v == "2"
+        ));
+        assert!(matches!(
+            paint(&[3, 0, 104, 5, 104, 0, 99]),
+            Err(Error::Protocol(ProtocolError::UnknownColor(v))) if v == "5"
+        ));
+        assert!(matches!(
+            paint(&[99]),
+            Err(Error::Protocol(ProtocolError::UnusedInput))
+        ));
+    }
+}
@@ -0,0 +1,258 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::hash::Hash;
+use std::io::Write;
+
+use crate::error::Error;
+
+/// An 8-bit RGB color.
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+/// Maps values to colors.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Palette<K: Hash + Eq> {
+    colors: HashMap<K, Rgb>,
+    background: Rgb,
+}
+
+impl<K: Hash + Eq> Palette<K> {
+    /// Creates a palette which maps every value to the background color.
+    #[must_use]
+    pub fn new(background: Rgb) -> Self {
+        Palette {
+            colors: HashMap::new(),
+            background,
+        }
+    }
+
+    #[must_use]
+    pub fn with(mut self, key: K, color: Rgb) -> Self {
+        self.colors.insert(key, color);
+        self
+    }
+
+    /// The color for a value, or the background color if there is no value.
+    #[must_use]
+    pub fn color(&self, key: Option<&K>) -> Rgb {
+        key.and_then(|k| self.colors.get(k))
+            .copied()
+            .unwrap_or(self.background)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum Format {
+    Ppm,
+    Png,
+}
+
+impl Format {
+    #[must_use]
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Ppm => "ppm",
+            Format::Png => "png",
+        }
+    }
+}
+
+/// A grid of pixels.
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Image {
+    /// Creates an image from a grid of cells, drawing each cell as a `scale` by `scale` square.
+    pub fn from_cells<F>(width: usize, height: usize, scale: usize, mut cell: F) -> Self
+    where
+        F: FnMut(usize, usize) -> Rgb,
+    {
+        let mut pixels = Vec::with_capacity(width * height * scale * scale);
+        for y in 0..height {
+            let row = (0..width)
+                .flat_map(|x| std::iter::repeat_n(cell(x, y), scale))
+                .collect::<Vec<_>>();
+            for _ in 0..scale {
+                pixels.extend_from_slice(&row);
+            }
+        }
+        Image {
+            width: width * scale,
+            height: height * scale,
+            pixels,
+        }
+    }
+
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    #[must_use]
+    pub fn get(&self, x: usize, y: usize) -> Option<Rgb> {
+        if x < self.width && y < self.height {
+            Some(self.pixels[y * self.width + x])
+        } else {
+            None
+        }
+    }
+
+    pub fn write<W: Write>(&self, format: Format, writer: W) -> Result<(), Error> {
+        match format {
+            Format::Ppm => self.write_ppm(writer),
+            Format::Png => self.write_png(writer),
+        }
+    }
+
+    /// Writes a binary (P6) PPM image.
+    pub fn write_ppm<W: Write>(&self, mut writer: W) -> Result<(), Error> {
+        write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+        let bytes = self
+            .pixels
+            .iter()
+            .flat_map(|&Rgb(r, g, b)| [r, g, b])
+            .collect::<Vec<_>>();
+        writer.write_all(&bytes)?;
+        Ok(())
+    }
+
+    /// Writes a PNG image with uncompressed image data.
+    pub fn write_png<W: Write>(&self, mut writer: W) -> Result<(), Error> {
+        let width = u32::try_from(self.width)?;
+        let height = u32::try_from(self.height)?;
+
+        writer.write_all(&[0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'])?;
+
+        let mut header = Vec::new();
+        header.extend_from_slice(&width.to_be_bytes());
+        header.extend_from_slice(&height.to_be_bytes());
+        // 8 bits per channel, RGB, default compression, filtering and no interlacing
+        header.extend_from_slice(&[8, 2, 0, 0, 0]);
+        write_chunk(&mut writer, b"IHDR", &header)?;
+
+        let mut raw = Vec::with_capacity(self.height * (self.width * 3 + 1));
+        for row in self.pixels.chunks(self.width.max(1)).take(self.height) {
+            // No filter
+            raw.push(0);
+            for &Rgb(r, g, b) in row {
+                raw.extend_from_slice(&[r, g, b]);
+            }
+        }
+        write_chunk(&mut writer, b"IDAT", &zlib_stored(&raw))?;
+        write_chunk(&mut writer, b"IEND", &[])?;
+        Ok(())
+    }
+}
+
+fn write_chunk<W: Write>(writer: &mut W, kind: &[u8; 4], data: &[u8]) -> Result<(), Error> {
+    writer.write_all(&u32::try_from(data.len())?.to_be_bytes())?;
+    writer.write_all(kind)?;
+    writer.write_all(data)?;
+    let mut crc_data = kind.to_vec();
+    crc_data.extend_from_slice(data);
+    writer.write_all(&crc32(&crc_data).to_be_bytes())?;
+    Ok(())
+}
+
+/// Wraps data in a zlib stream of uncompressed deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    const MAX_BLOCK: usize = 0xffff;
+
+    let mut out = vec![0x78, 0x01];
+    let mut blocks = data.chunks(MAX_BLOCK).peekable();
+    if blocks.peek().is_none() {
+        out.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let is_final = blocks.peek().is_none();
+        out.push(u8::from(is_final));
+        let len = block.len() as u16;
+        out.extend_from_slice(&len.to_le_bytes());
+        out.extend_from_slice(&(!len).to_le_bytes());
+        out.extend_from_slice(block);
+    }
+    out.extend_from_slice(&adler32(data).to_be_bytes());
+    out
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = 0xffff_ffff_u32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1_u32, 0_u32);
+    for &byte in data {
+        a = (a + u32::from(byte)) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checksums() {
+        assert_eq!(0xcbf4_3926, crc32(b"123456789"));
+        assert_eq!(0x11e6_0398, adler32(b"Wikipedia"));
+    }
+
+    #[test]
+    fn scaled_cells() {
+        let palette = Palette::new(Rgb(0, 0, 0)).with(true, Rgb(255, 255, 255));
+        let image = Image::from_cells(2, 1, 2, |x, _| palette.color(Some(&(x == 1))));
+        assert_eq!(4, image.width());
+        assert_eq!(2, image.height());
+        assert_eq!(Some(Rgb(0, 0, 0)), image.get(1, 1));
+        assert_eq!(Some(Rgb(255, 255, 255)), image.get(2, 1));
+        assert_eq!(None, image.get(0, 2));
+
+        let mut ppm = Vec::new();
+        image.write_ppm(&mut ppm).unwrap();
+        assert!(ppm.starts_with(b"P6\n4 2\n255\n"));
+        assert_eq!(b"P6\n4 2\n255\n".len() + 4 * 2 * 3, ppm.len());
+    }
+
+    #[test]
+    fn png_layout() {
+        let image = Image::from_cells(3, 2, 1, |x, y| Rgb(x as u8, y as u8, 7));
+        let mut png = Vec::new();
+        image.write_png(&mut png).unwrap();
+
+        assert_eq!(&[0x89, b'P', b'N', b'G'], &png[..4]);
+        assert_eq!(b"IHDR", &png[12..16]);
+        assert_eq!(&[0, 0, 0, 3, 0, 0, 0, 2, 8, 2, 0, 0, 0], &png[16..29]);
+        assert_eq!(b"IDAT", &png[37..41]);
+
+        // Each row is a filter byte followed by the pixels.
+        let idat_len = u32::from_be_bytes([png[33], png[34], png[35], png[36]]) as usize;
+        let zlib = &png[41..41 + idat_len];
+        let raw = &zlib[7..zlib.len() - 4];
+        assert_eq!(
+            &[0, 0, 0, 7, 1, 0, 7, 2, 0, 7, 0, 0, 1, 7, 1, 1, 7, 2, 1, 7],
+            raw
+        );
+        assert_eq!(b"IEND", &png[png.len() - 8..png.len() - 4]);
+    }
+}
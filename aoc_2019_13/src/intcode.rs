@@ -2,6 +2,7 @@ use std::collections::VecDeque;
 use std::convert::TryFrom;
 use std::io;
 
+use crate::device::DeviceBus;
 use crate::error::Error;
 
 /// Used to read input for the program.
@@ -129,10 +130,24 @@ fn decode_op_code(op: i64) -> OpCode {
 #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
 pub enum ProgState {
     NotStarted,
+    Running,
     Halt,
     NeedInput,
 }
 
+/// The state needed to undo a single executed instruction.
+///
+/// Writes to devices are not recorded and cannot be undone.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub(crate) struct Undo {
+    pub(crate) pc: usize,
+    pub(crate) relative_base: isize,
+    pub(crate) state: ProgState,
+    pub(crate) mem_len: usize,
+    /// The address written to and its previous value.
+    pub(crate) write: Option<(usize, i64)>,
+}
+
 #[derive(Clone, Debug, Hash, Eq, PartialEq)]
 pub struct Prog {
     mem_state: Vec<i64>,
@@ -157,139 +172,238 @@ impl Prog {
     #[must_use] pub fn state(&self) -> ProgState {
         self.state
     }
+
+    #[must_use] pub fn mem_state(&self) -> &[i64] {
+        &self.mem_state
+    }
+
+    #[must_use] pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    #[must_use] pub fn relative_base(&self) -> isize {
+        self.relative_base
+    }
 }
 
 impl Prog {
-    fn get_operand(&mut self, param_num: usize, op_code: i64) -> Result<i64, Error> {
+    fn address(&self, param_num: usize, op_code: i64) -> Result<usize, Error> {
         match param_mode(u32::try_from(param_num)?, op_code) {
-            ParamMode::Position => {
-                let index = usize::try_from(self.mem_state[self.pc + (param_num + 1)])?;
-                if index >= self.mem_state.len() {
-                    self.mem_state.resize(index + 1, 0);
-                }
-                Ok(self.mem_state[index])
-            }
-            ParamMode::Immediate => Ok(self.mem_state[self.pc + (param_num + 1)]),
-            ParamMode::Relative => {
-                let index = usize::try_from(
-                    isize::try_from(self.mem_state[self.pc + (param_num + 1)])?
-                        + self.relative_base,
-                )?;
-                if index >= self.mem_state.len() {
-                    self.mem_state.resize(index + 1, 0);
-                }
-                Ok(self.mem_state[index])
-            }
+            ParamMode::Position => Ok(usize::try_from(self.mem_state[self.pc + (param_num + 1)])?),
+            ParamMode::Immediate => unreachable!(),
+            ParamMode::Relative => Ok(usize::try_from(
+                isize::try_from(self.mem_state[self.pc + (param_num + 1)])? + self.relative_base,
+            )?),
         }
     }
 
-    fn store_value(&mut self, value: i64, param_num: usize, op_code: i64) -> Result<(), Error> {
-        match param_mode(u32::try_from(param_num)?, op_code) {
-            ParamMode::Position => {
-                let index = usize::try_from(self.mem_state[self.pc + (param_num + 1)])?;
-                if index >= self.mem_state.len() {
-                    self.mem_state.resize(index + 1, 0);
-                }
-                self.mem_state[index] = value;
-                Ok(())
-            }
-            ParamMode::Immediate => unreachable!(),
-            ParamMode::Relative => {
-                let index = usize::try_from(
-                    isize::try_from(self.mem_state[self.pc + (param_num + 1)])?
-                        + self.relative_base,
-                )?;
-                if index >= self.mem_state.len() {
-                    self.mem_state.resize(index + 1, 0);
-                }
-                self.mem_state[index] = value;
-                Ok(())
-            }
+    fn get_operand(
+        &mut self,
+        param_num: usize,
+        op_code: i64,
+        bus: &mut DeviceBus<'_>,
+    ) -> Result<i64, Error> {
+        if param_mode(u32::try_from(param_num)?, op_code) == ParamMode::Immediate {
+            return Ok(self.mem_state[self.pc + (param_num + 1)]);
+        }
+
+        let index = self.address(param_num, op_code)?;
+        if let Some(value) = bus.read(index)? {
+            return Ok(value);
         }
+        if index >= self.mem_state.len() {
+            self.mem_state.resize(index + 1, 0);
+        }
+        Ok(self.mem_state[index])
+    }
+
+    fn store_value(
+        &mut self,
+        value: i64,
+        param_num: usize,
+        op_code: i64,
+        bus: &mut DeviceBus<'_>,
+    ) -> Result<Option<(usize, i64)>, Error> {
+        let index = self.address(param_num, op_code)?;
+        if bus.write(index, value)? {
+            return Ok(None);
+        }
+        if index >= self.mem_state.len() {
+            self.mem_state.resize(index + 1, 0);
+        }
+        let prev = self.mem_state[index];
+        self.mem_state[index] = value;
+        Ok(Some((index, prev)))
     }
 
     /// Runs a program given an initial memory state.
     pub fn run<T, S>(&mut self, input: &mut T, output: &mut S) -> Result<(), Error>
+    where
+        T: ProgInput,
+        S: ProgOutput,
+    {
+        self.run_with_devices(input, output, &mut DeviceBus::new())
+    }
+
+    /// Runs a program with devices intercepting reads and writes to their mapped addresses.
+    ///
+    /// Only operands and stored values go through the bus. Instructions and immediate
+    /// parameters are always read from program memory.
+    pub fn run_with_devices<T, S>(
+        &mut self,
+        input: &mut T,
+        output: &mut S,
+        bus: &mut DeviceBus<'_>,
+    ) -> Result<(), Error>
     where
         T: ProgInput,
         S: ProgOutput,
     {
         loop {
-            let op_code = self.mem_state[self.pc];
-            match decode_op_code(op_code) {
-                OpCode::Add => {
-                    let operand_0 = self.get_operand(0, op_code)?;
-                    let operand_1 = self.get_operand(1, op_code)?;
-                    self.store_value(operand_0 + operand_1, 2, op_code)?;
-                    self.pc += 4;
-                }
-                OpCode::Mul => {
-                    let operand_0 = self.get_operand(0, op_code)?;
-                    let operand_1 = self.get_operand(1, op_code)?;
-                    self.store_value(operand_0 * operand_1, 2, op_code)?;
-                    self.pc += 4;
-                }
-                OpCode::Input => {
-                    let input = match input.read() {
-                        Ok(v) => v,
-                        Err(Error::NoAvailableInput) => {
-                            self.state = ProgState::NeedInput;
-                            return Ok(());
-                        }
-                        Err(e) => return Err(e),
-                    };
-                    let input = input.trim().parse::<i64>()?;
-
-                    self.store_value(input, 0, op_code)?;
-                    self.pc += 2;
-                }
-                OpCode::Output => {
-                    let operand_0 = self.get_operand(0, op_code)?;
-                    output.write(&format!("{operand_0}"))?;
-                    self.pc += 2;
-                }
-                OpCode::JumpIfTrue => {
-                    let operand_0 = self.get_operand(0, op_code)?;
-                    if operand_0 != 0 {
-                        let operand_1 = self.get_operand(1, op_code)?;
-                        self.pc = usize::try_from(operand_1)?;
-                    } else {
-                        self.pc += 3;
-                    }
-                }
-                OpCode::JumpIfFalse => {
-                    let operand_0 = self.get_operand(0, op_code)?;
-                    if operand_0 == 0 {
-                        let operand_1 = self.get_operand(1, op_code)?;
-                        self.pc = usize::try_from(operand_1)?;
-                    } else {
-                        self.pc += 3;
+            self.execute(input, output, bus)?;
+            match self.state {
+                ProgState::Halt | ProgState::NeedInput => return Ok(()),
+                ProgState::NotStarted | ProgState::Running => {}
+            }
+        }
+    }
+
+    /// Executes a single instruction.
+    pub fn step<T, S>(&mut self, input: &mut T, output: &mut S) -> Result<(), Error>
+    where
+        T: ProgInput,
+        S: ProgOutput,
+    {
+        self.execute(input, output, &mut DeviceBus::new())?;
+        Ok(())
+    }
+
+    /// Executes a single instruction and returns how to undo it.
+    pub(crate) fn step_with_undo<T, S>(
+        &mut self,
+        input: &mut T,
+        output: &mut S,
+    ) -> Result<Undo, Error>
+    where
+        T: ProgInput,
+        S: ProgOutput,
+    {
+        let pc = self.pc;
+        let relative_base = self.relative_base;
+        let state = self.state;
+        let mem_len = self.mem_state.len();
+        let write = self.execute(input, output, &mut DeviceBus::new())?;
+        Ok(Undo {
+            pc,
+            relative_base,
+            state,
+            mem_len,
+            write,
+        })
+    }
+
+    /// Reverts the effects of an instruction on the program.
+    pub(crate) fn undo(&mut self, undo: Undo) {
+        if let Some((index, prev)) = undo.write {
+            self.mem_state[index] = prev;
+        }
+        self.mem_state.truncate(undo.mem_len);
+        self.pc = undo.pc;
+        self.relative_base = undo.relative_base;
+        self.state = undo.state;
+    }
+
+    /// Executes the instruction at the program counter.
+    ///
+    /// Returns the memory address written to and its previous value.
+    fn execute<T, S>(
+        &mut self,
+        input: &mut T,
+        output: &mut S,
+        bus: &mut DeviceBus<'_>,
+    ) -> Result<Option<(usize, i64)>, Error>
+    where
+        T: ProgInput,
+        S: ProgOutput,
+    {
+        let op_code = self.mem_state[self.pc];
+        let mut write = None;
+        match decode_op_code(op_code) {
+            OpCode::Add => {
+                let operand_0 = self.get_operand(0, op_code, bus)?;
+                let operand_1 = self.get_operand(1, op_code, bus)?;
+                write = self.store_value(operand_0 + operand_1, 2, op_code, bus)?;
+                self.pc += 4;
+            }
+            OpCode::Mul => {
+                let operand_0 = self.get_operand(0, op_code, bus)?;
+                let operand_1 = self.get_operand(1, op_code, bus)?;
+                write = self.store_value(operand_0 * operand_1, 2, op_code, bus)?;
+                self.pc += 4;
+            }
+            OpCode::Input => {
+                let input = match input.read() {
+                    Ok(v) => v,
+                    Err(Error::NoAvailableInput) => {
+                        self.state = ProgState::NeedInput;
+                        return Ok(None);
                     }
+                    Err(e) => return Err(e),
+                };
+                let input = input.trim().parse::<i64>()?;
+
+                write = self.store_value(input, 0, op_code, bus)?;
+                self.pc += 2;
+            }
+            OpCode::Output => {
+                let operand_0 = self.get_operand(0, op_code, bus)?;
+                output.write(&format!("{operand_0}"))?;
+                self.pc += 2;
+            }
+            OpCode::JumpIfTrue => {
+                let operand_0 = self.get_operand(0, op_code, bus)?;
+                if operand_0 != 0 {
+                    let operand_1 = self.get_operand(1, op_code, bus)?;
+                    self.pc = usize::try_from(operand_1)?;
+                } else {
+                    self.pc += 3;
                 }
-                OpCode::LessThan => {
-                    let operand_0 = self.get_operand(0, op_code)?;
-                    let operand_1 = self.get_operand(1, op_code)?;
-                    self.store_value(i64::from(operand_0 < operand_1), 2, op_code)?;
-                    self.pc += 4;
-                }
-                OpCode::Equals => {
-                    let operand_0 = self.get_operand(0, op_code)?;
-                    let operand_1 = self.get_operand(1, op_code)?;
-                    self.store_value(i64::from(operand_0 == operand_1), 2, op_code)?;
-                    self.pc += 4;
-                }
-                OpCode::AdjustsRelativeBase => {
-                    let operand_0 = self.get_operand(0, op_code)?;
-                    self.relative_base =
-                        isize::try_from(i64::try_from(self.relative_base)? + operand_0)?;
-                    self.pc += 2;
-                }
-                OpCode::Halt => {
-                    self.state = ProgState::Halt;
-                    return Ok(());
+            }
+            OpCode::JumpIfFalse => {
+                let operand_0 = self.get_operand(0, op_code, bus)?;
+                if operand_0 == 0 {
+                    let operand_1 = self.get_operand(1, op_code, bus)?;
+                    self.pc = usize::try_from(operand_1)?;
+                } else {
+                    self.pc += 3;
                 }
             }
+            OpCode::LessThan => {
+                let operand_0 = self.get_operand(0, op_code, bus)?;
+                let operand_1 = self.get_operand(1, op_code, bus)?;
+                write = self.store_value(i64::from(operand_0 < operand_1), 2, op_code, bus)?;
+                self.pc += 4;
+            }
+            OpCode::Equals => {
+                let operand_0 = self.get_operand(0, op_code, bus)?;
+                let operand_1 = self.get_operand(1, op_code, bus)?;
+                write = self.store_value(i64::from(operand_0 == operand_1), 2, op_code, bus)?;
+                self.pc += 4;
+            }
+            OpCode::AdjustsRelativeBase => {
+                let operand_0 = self.get_operand(0, op_code, bus)?;
+                self.relative_base =
+                    isize::try_from(i64::try_from(self.relative_base)? + operand_0)?;
+                self.pc += 2;
+            }
+            OpCode::Halt => {
+                self.state = ProgState::Halt;
+                return Ok(None);
+            }
         }
+
+        self.state = ProgState::Running;
+        Ok(write)
     }
 }
 
@@ -1,8 +1,15 @@
 pub mod amp;
 pub mod arcade;
+pub mod controller;
+pub mod debugger;
+pub mod device;
 pub mod error;
+pub mod exec;
 pub mod hull_robot;
+pub mod image;
 pub mod intcode;
+pub mod play;
+pub mod replay;
 
 #[cfg(test)]
 mod tests {
@@ -1,7 +1,14 @@
+use std::env;
+use std::fs;
 use std::io::{self};
+use std::process;
 
-use aoc_2019_13::{self, error::Error, intcode::Prog};
+use aoc_2019_13::{self, controller::FollowBall, error::Error, intcode::Prog, replay::GameLog};
 
+/// Reads the program from stdin and prints the final score.
+///
+/// `--log csv` or `--log json` prints a log of every input instead. `--check <log.csv>` replays a
+/// log's inputs and verifies the game matches it.
 fn main() -> Result<(), Error> {
     let mut input = String::new();
     let _ = io::stdin().read_line(&mut input)?;
@@ -9,9 +16,41 @@ fn main() -> Result<(), Error> {
     mem_state[0] = 2;
 
     let prog = Prog::new(&mem_state);
-    let score = aoc_2019_13::arcade::run_loop(prog)?;
-
-    println!("{score}");
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    match args.iter().map(String::as_str).collect::<Vec<_>>()[..] {
+        [] => {
+            let result = aoc_2019_13::arcade::run_loop(prog, &mut FollowBall)?;
+            println!("{}", result.score);
+        }
+        ["--log", format] => {
+            let log = aoc_2019_13::replay::run_loop_with_log(prog, &mut FollowBall)?;
+            match format {
+                "csv" => print!("{}", log.to_csv()),
+                "json" => println!("{}", log.to_json()),
+                _ => {
+                    eprintln!("unknown log format: {format}");
+                    process::exit(2);
+                }
+            }
+        }
+        ["--check", path] => {
+            let log = fs::read_to_string(path)?.parse::<GameLog>()?;
+            let check = aoc_2019_13::replay::check_replay(prog, &log)?;
+            if check.is_ok() {
+                println!("ok: {}", check.actual_score);
+            } else {
+                println!(
+                    "mismatch at frame {:?}: expected score {:?}, got {}",
+                    check.first_mismatch, check.expected_score, check.actual_score
+                );
+                process::exit(1);
+            }
+        }
+        _ => {
+            eprintln!("usage: aoc_2019_13 [--log csv|json | --check <log.csv>] < input");
+            process::exit(2);
+        }
+    }
 
     Ok(())
 }
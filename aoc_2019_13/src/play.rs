@@ -0,0 +1,287 @@
+use std::io::{BufRead, Write};
+
+use crate::arcade::{Joystick, Screen};
+use crate::controller::Controller;
+use crate::error::Error;
+
+/// A command from the player.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum Key {
+    Move(Joystick),
+    /// Undo the last number of inputs.
+    Rewind(usize),
+    Pause,
+    Quit,
+}
+
+/// Reads a key from a terminal in raw mode.
+///
+/// Arrow keys and `a`/`d` move, space leaves the joystick neutral, `r` rewinds one input, `R`
+/// rewinds ten inputs, `p` or Esc pauses and `q` quits. Returns `None` at the end of input.
+///
+/// A terminal sends an escape sequence in a single read, so an ESC byte without the rest of a
+/// sequence already buffered is the Esc key.
+pub fn read_raw_key<R: BufRead>(reader: &mut R) -> Result<Option<Key>, Error> {
+    loop {
+        let buf = reader.fill_buf()?;
+        let (len, key) = match buf {
+            [] => return Ok(None),
+            [0x1b, b'[', b'D', ..] => (3, Some(Key::Move(Joystick::Left))),
+            [0x1b, b'[', b'C', ..] => (3, Some(Key::Move(Joystick::Right))),
+            [0x1b, b'[', _, ..] => (3, None),
+            [0x1b, ..] => (1, Some(Key::Pause)),
+            [b, ..] => (
+                1,
+                match b {
+                    b'a' => Some(Key::Move(Joystick::Left)),
+                    b'd' => Some(Key::Move(Joystick::Right)),
+                    b' ' => Some(Key::Move(Joystick::Neutral)),
+                    b'r' => Some(Key::Rewind(1)),
+                    b'R' => Some(Key::Rewind(10)),
+                    b'p' => Some(Key::Pause),
+                    b'q' => Some(Key::Quit),
+                    _ => None,
+                },
+            ),
+        };
+        reader.consume(len);
+        if key.is_some() {
+            return Ok(key);
+        }
+    }
+}
+
+/// Reads a key from a line of input.
+///
+/// `a` and `d` move, a space or an empty line leaves the joystick neutral, `r` or `r N`
+/// rewinds one or `N` inputs, `p` pauses and `q` quits. Returns `None` at the end of input.
+pub fn read_line_key<R: BufRead>(reader: &mut R) -> Result<Option<Key>, Error> {
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end_matches(&['\r', '\n'][..]);
+        let key = match line {
+            "a" => Some(Key::Move(Joystick::Left)),
+            "d" => Some(Key::Move(Joystick::Right)),
+            "" | " " => Some(Key::Move(Joystick::Neutral)),
+            "p" => Some(Key::Pause),
+            "q" => Some(Key::Quit),
+            "r" => Some(Key::Rewind(1)),
+            _ => line
+                .strip_prefix("r ")
+                .and_then(|n| n.trim().parse().ok())
+                .map(Key::Rewind),
+        };
+        if key.is_some() {
+            return Ok(key);
+        }
+    }
+}
+
+/// Renders each frame and asks the player for the joystick input.
+#[derive(Debug)]
+pub struct Player<R, W> {
+    reader: R,
+    writer: W,
+    raw: bool,
+    quit: bool,
+    score: i64,
+    blocks: usize,
+}
+
+impl<R: BufRead, W: Write> Player<R, W> {
+    /// Creates a player reading keys from `reader`.
+    ///
+    /// In raw mode each key is a single keypress and the terminal is cleared between frames.
+    /// Otherwise each key is a line of input.
+    pub fn new(reader: R, writer: W, raw: bool) -> Self {
+        Player {
+            reader,
+            writer,
+            raw,
+            quit: false,
+            score: 0,
+            blocks: 0,
+        }
+    }
+
+    /// True if the player quit or ran out of input before the game ended.
+    #[must_use]
+    pub fn quit(&self) -> bool {
+        self.quit
+    }
+
+    /// The score in the last rendered frame.
+    #[must_use]
+    pub fn score(&self) -> i64 {
+        self.score
+    }
+
+    /// The blocks remaining in the last rendered frame.
+    #[must_use]
+    pub fn blocks(&self) -> usize {
+        self.blocks
+    }
+
+    fn read_key(&mut self) -> Result<Option<Key>, Error> {
+        if self.raw {
+            read_raw_key(&mut self.reader)
+        } else {
+            read_line_key(&mut self.reader)
+        }
+    }
+
+    fn render(&mut self, screen: &Screen, status: &str) -> Result<(), Error> {
+        self.score = screen.score();
+        self.blocks = screen.blocks_remaining();
+
+        if self.raw {
+            write!(self.writer, "\x1b[H\x1b[2J")?;
+        }
+        write!(self.writer, "{screen}")?;
+        writeln!(
+            self.writer,
+            "Score: {}  Blocks: {}  {status}",
+            self.score, self.blocks
+        )?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+impl<R: BufRead, W: Write> Player<R, W> {
+    /// Renders the screen and reads keys until the player moves, rewinds or quits.
+    ///
+    /// Pausing is handled here, so `Key::Pause` is never returned. Reaching the end of input
+    /// returns `Key::Quit`.
+    pub fn next_key(&mut self, screen: &Screen) -> Result<Key, Error> {
+        let mut paused = false;
+        loop {
+            let status = if paused {
+                "Paused (p to resume, q to quit)"
+            } else {
+                "(a/d or arrows to move, r to rewind, p to pause, q to quit)"
+            };
+            self.render(screen, status)?;
+
+            match self.read_key()? {
+                Some(Key::Move(_)) | Some(Key::Rewind(_)) if paused => {}
+                Some(Key::Pause) => paused = !paused,
+                Some(Key::Quit) | None => {
+                    self.quit = true;
+                    return Ok(Key::Quit);
+                }
+                Some(key) => return Ok(key),
+            }
+        }
+    }
+}
+
+impl<R: BufRead, W: Write> Controller for Player<R, W> {
+    /// Returns `Error::NoAvailableInput` if the player quits. Rewinding is ignored.
+    fn joystick(&mut self, screen: &Screen) -> Result<Joystick, Error> {
+        loop {
+            match self.next_key(screen)? {
+                Key::Move(joystick) => return Ok(joystick),
+                Key::Rewind(_) | Key::Pause => {}
+                Key::Quit => return Err(Error::NoAvailableInput),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arcade::{Pos, Tile, Type};
+
+    #[test]
+    fn raw_keys() {
+        let mut input = "\x1b[Dx\x1b[C rRpq".as_bytes();
+        let keys = std::iter::from_fn(|| read_raw_key(&mut input).unwrap()).collect::<Vec<_>>();
+        assert_eq!(
+            vec![
+                Key::Move(Joystick::Left),
+                Key::Move(Joystick::Right),
+                Key::Move(Joystick::Neutral),
+                Key::Rewind(1),
+                Key::Rewind(10),
+                Key::Pause,
+                Key::Quit,
+            ],
+            keys
+        );
+    }
+
+    #[test]
+    fn bare_escape() {
+        // Only the escape is available, as when the Esc key is pressed alone.
+        let mut input = "\x1b".as_bytes();
+        assert_eq!(Some(Key::Pause), read_raw_key(&mut input).unwrap());
+        assert_eq!(None, read_raw_key(&mut input).unwrap());
+
+        // An unknown sequence is skipped as a whole, and a lone escape before a key is its own
+        // key.
+        let mut input = "\x1b[Aq\x1bd".as_bytes();
+        let keys = std::iter::from_fn(|| read_raw_key(&mut input).unwrap()).collect::<Vec<_>>();
+        assert_eq!(
+            vec![Key::Quit, Key::Pause, Key::Move(Joystick::Right)],
+            keys
+        );
+
+        // Reading a bare escape does not wait for more input from a reader which would block.
+        let mut reader = std::io::BufReader::new(OneRead(Some(b"\x1b".to_vec())));
+        assert_eq!(Some(Key::Pause), read_raw_key(&mut reader).unwrap());
+    }
+
+    /// Returns its bytes from the first read and panics on any later read, like a terminal with
+    /// no more key presses.
+    struct OneRead(Option<Vec<u8>>);
+
+    impl std::io::Read for OneRead {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let bytes = self.0.take().expect("no read after the key press");
+            buf[..bytes.len()].copy_from_slice(&bytes);
+            Ok(bytes.len())
+        }
+    }
+
+    #[test]
+    fn line_based_play() {
+        let mut screen = Screen::new();
+        screen.set(Tile {
+            pos: Pos { x: 0, y: 0 },
+            tile_id: Type::Block,
+        });
+        screen.set(Tile {
+            pos: Pos { x: 1, y: 1 },
+            tile_id: Type::Ball,
+        });
+
+        let mut output = Vec::new();
+        let mut player = Player::new("a\np\nd\np\n\nq\n".as_bytes(), &mut output, false);
+        assert_eq!(Joystick::Left, player.joystick(&screen).unwrap());
+        // Moves while paused are ignored.
+        assert_eq!(Joystick::Neutral, player.joystick(&screen).unwrap());
+        assert!(!player.quit());
+        assert!(matches!(
+            player.joystick(&screen),
+            Err(Error::NoAvailableInput)
+        ));
+        assert!(player.quit());
+        assert_eq!(1, player.blocks());
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.starts_with("=\n *\nScore: 0  Blocks: 1  "));
+        assert!(output.contains("Paused"));
+    }
+
+    #[test]
+    fn line_rewind_keys() {
+        let mut input = "r\nr 5\nr x\nq\n".as_bytes();
+        let keys = std::iter::from_fn(|| read_line_key(&mut input).unwrap()).collect::<Vec<_>>();
+        assert_eq!(vec![Key::Rewind(1), Key::Rewind(5), Key::Quit], keys);
+    }
+}
@@ -0,0 +1,289 @@
+use std::fmt::Write;
+use std::str::FromStr;
+
+use crate::arcade::{Game, Joystick, Pos, Screen};
+use crate::controller::{Controller, Scripted};
+use crate::error::Error;
+use crate::intcode::Prog;
+
+const CSV_HEADER: &str = "frame,joystick,ball_x,ball_y,paddle_x,paddle_y,blocks_remaining,\
+                          blocks_destroyed,score,score_change";
+
+/// The state of the game when an input was given.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct LogEntry {
+    pub frame: usize,
+    /// The input given, or `None` for the final entry after the game ends.
+    pub joystick: Option<Joystick>,
+    pub ball: Option<Pos>,
+    pub paddle: Option<Pos>,
+    pub blocks_remaining: usize,
+    /// The blocks destroyed since the previous entry.
+    pub blocks_destroyed: usize,
+    pub score: i64,
+    /// The change in score since the previous entry.
+    pub score_change: i64,
+}
+
+/// A log of every input given during a game.
+#[derive(Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub struct GameLog {
+    entries: Vec<LogEntry>,
+}
+
+impl GameLog {
+    #[must_use]
+    pub fn entries(&self) -> &[LogEntry] {
+        &self.entries
+    }
+
+    #[must_use]
+    pub fn final_score(&self) -> Option<i64> {
+        self.entries.last().map(|e| e.score)
+    }
+
+    /// The inputs given, in order.
+    pub fn joysticks(&self) -> impl Iterator<Item = Joystick> + '_ {
+        self.entries.iter().filter_map(|e| e.joystick)
+    }
+
+    fn push(&mut self, screen: &Screen, joystick: Option<Joystick>) {
+        let (prev_blocks, prev_score) = self
+            .entries
+            .last()
+            .map_or((screen.blocks_remaining(), 0), |e| {
+                (e.blocks_remaining, e.score)
+            });
+        self.entries.push(LogEntry {
+            frame: self.entries.len(),
+            joystick,
+            ball: screen.ball(),
+            paddle: screen.paddle(),
+            blocks_remaining: screen.blocks_remaining(),
+            blocks_destroyed: prev_blocks.saturating_sub(screen.blocks_remaining()),
+            score: screen.score(),
+            score_change: screen.score() - prev_score,
+        });
+    }
+
+    #[must_use]
+    pub fn to_csv(&self) -> String {
+        fn opt<T: ToString>(v: Option<T>) -> String {
+            v.map(|v| v.to_string()).unwrap_or_default()
+        }
+
+        let mut csv = String::from(CSV_HEADER);
+        csv.push('\n');
+        for e in &self.entries {
+            writeln!(
+                csv,
+                "{},{},{},{},{},{},{},{},{},{}",
+                e.frame,
+                opt(e.joystick.map(String::from)),
+                opt(e.ball.map(|p| p.x)),
+                opt(e.ball.map(|p| p.y)),
+                opt(e.paddle.map(|p| p.x)),
+                opt(e.paddle.map(|p| p.y)),
+                e.blocks_remaining,
+                e.blocks_destroyed,
+                e.score,
+                e.score_change
+            )
+            .expect("writing to a string to succeed");
+        }
+        csv
+    }
+
+    #[must_use]
+    pub fn to_json(&self) -> String {
+        fn opt<T: ToString>(v: Option<T>) -> String {
+            v.map_or_else(|| String::from("null"), |v| v.to_string())
+        }
+        fn pos(p: Option<Pos>) -> String {
+            p.map_or_else(
+                || String::from("null"),
+                |p| format!("{{\"x\":{},\"y\":{}}}", p.x, p.y),
+            )
+        }
+
+        let entries = self
+            .entries
+            .iter()
+            .map(|e| {
+                format!(
+                    "{{\"frame\":{},\"joystick\":{},\"ball\":{},\"paddle\":{},\
+                     \"blocks_remaining\":{},\"blocks_destroyed\":{},\"score\":{},\
+                     \"score_change\":{}}}",
+                    e.frame,
+                    opt(e.joystick.map(String::from)),
+                    pos(e.ball),
+                    pos(e.paddle),
+                    e.blocks_remaining,
+                    e.blocks_destroyed,
+                    e.score,
+                    e.score_change
+                )
+            })
+            .collect::<Vec<_>>();
+        format!("[{}]", entries.join(","))
+    }
+}
+
+impl FromStr for GameLog {
+    type Err = Error;
+
+    /// Parses a log written by `to_csv`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        fn opt<T: FromStr>(field: &str) -> Result<Option<T>, Error>
+        where
+            Error: From<T::Err>,
+        {
+            if field.is_empty() {
+                Ok(None)
+            } else {
+                Ok(Some(field.parse()?))
+            }
+        }
+        fn pos(x: &str, y: &str) -> Result<Option<Pos>, Error> {
+            Ok(match (opt(x)?, opt(y)?) {
+                (Some(x), Some(y)) => Some(Pos { x, y }),
+                _ => None,
+            })
+        }
+
+        let mut lines = s.lines();
+        if lines.next() != Some(CSV_HEADER) {
+            return Err(Error::UnknownValue);
+        }
+
+        let entries = lines
+            .filter(|l| !l.is_empty())
+            .map(|line| {
+                let fields = line.split(',').collect::<Vec<_>>();
+                if fields.len() != 10 {
+                    return Err(Error::UnknownValue);
+                }
+                Ok(LogEntry {
+                    frame: fields[0].parse()?,
+                    joystick: opt(fields[1])?,
+                    ball: pos(fields[2], fields[3])?,
+                    paddle: pos(fields[4], fields[5])?,
+                    blocks_remaining: fields[6].parse()?,
+                    blocks_destroyed: fields[7].parse()?,
+                    score: fields[8].parse()?,
+                    score_change: fields[9].parse()?,
+                })
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+        Ok(GameLog { entries })
+    }
+}
+
+/// Plays a game, logging the state at every input and when the game ends.
+pub fn run_loop_with_log<C: Controller>(prog: Prog, controller: &mut C) -> Result<GameLog, Error> {
+    let mut log = GameLog::default();
+    let mut game = Game::new(prog)?;
+    while !game.is_over() {
+        let joystick = controller.joystick(game.screen())?;
+        log.push(game.screen(), Some(joystick));
+        game.input(joystick)?;
+    }
+    log.push(game.screen(), None);
+    Ok(log)
+}
+
+/// The outcome of replaying a log.
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub struct ReplayCheck {
+    pub expected_score: Option<i64>,
+    pub actual_score: i64,
+    /// The first entry which differs from the replayed game.
+    pub first_mismatch: Option<usize>,
+}
+
+impl ReplayCheck {
+    #[must_use]
+    pub fn is_ok(&self) -> bool {
+        self.first_mismatch.is_none() && self.expected_score == Some(self.actual_score)
+    }
+}
+
+/// Replays the logged inputs against a program and compares the resulting game with the log.
+///
+/// Returns `Error::NoAvailableInput` if the program wants more inputs than were logged.
+pub fn check_replay(prog: Prog, log: &GameLog) -> Result<ReplayCheck, Error> {
+    let mut scripted = Scripted::new(log.joysticks().collect());
+    let replayed = run_loop_with_log(prog, &mut scripted)?;
+
+    let first_mismatch = log
+        .entries
+        .iter()
+        .zip(replayed.entries.iter())
+        .position(|(a, b)| a != b)
+        .or_else(|| {
+            if log.entries.len() == replayed.entries.len() {
+                None
+            } else {
+                Some(log.entries.len().min(replayed.entries.len()))
+            }
+        });
+    Ok(ReplayCheck {
+        expected_score: log.final_score(),
+        actual_score: replayed.final_score().unwrap_or(0),
+        first_mismatch,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::controller::FollowBall;
+
+    /// Draws a block, a paddle and a ball, then clears the block and scores the input.
+    const MEM_STATE: &[i64] = &[
+        104, 0, 104, 0, 104, 2, 104, 1, 104, 2, 104, 3, 104, 2, 104, 1, 104, 4, 3, 100, 104, 0,
+        104, 0, 104, 0, 104, -1, 104, 0, 4, 100, 99,
+    ];
+
+    #[test]
+    fn log_formats() {
+        let log = run_loop_with_log(Prog::new(MEM_STATE), &mut FollowBall).unwrap();
+        assert_eq!(Some(1), log.final_score());
+        assert_eq!(vec![Joystick::Right], log.joysticks().collect::<Vec<_>>());
+
+        let csv = log.to_csv();
+        assert_eq!(
+            format!("{CSV_HEADER}\n0,1,2,1,1,2,1,0,0,0\n1,,2,1,1,2,0,1,1,1\n"),
+            csv
+        );
+        assert_eq!(log, csv.parse::<GameLog>().unwrap());
+
+        assert_eq!(
+            "[{\"frame\":0,\"joystick\":1,\"ball\":{\"x\":2,\"y\":1},\"paddle\":{\"x\":1,\"y\":2},\
+             \"blocks_remaining\":1,\"blocks_destroyed\":0,\"score\":0,\"score_change\":0},\
+             {\"frame\":1,\"joystick\":null,\"ball\":{\"x\":2,\"y\":1},\"paddle\":{\"x\":1,\"y\":2},\
+             \"blocks_remaining\":0,\"blocks_destroyed\":1,\"score\":1,\"score_change\":1}]",
+            log.to_json()
+        );
+    }
+
+    #[test]
+    fn replay() {
+        let log = run_loop_with_log(Prog::new(MEM_STATE), &mut FollowBall).unwrap();
+        assert!(check_replay(Prog::new(MEM_STATE), &log).unwrap().is_ok());
+
+        // A different program scoring twice the input.
+        let mut doubled = MEM_STATE.to_vec();
+        doubled.splice(30..32, vec![1002, 100, 2, 100, 4, 100]);
+        let check = check_replay(Prog::new(&doubled), &log).unwrap();
+        assert_eq!(
+            ReplayCheck {
+                expected_score: Some(1),
+                actual_score: 2,
+                first_mismatch: Some(1),
+            },
+            check
+        );
+        assert!(!check.is_ok());
+    }
+}
@@ -0,0 +1,56 @@
+use std::{
+    error,
+    fmt::{self, Debug, Display},
+    io, num,
+};
+
+#[derive(Debug)]
+pub enum Error {
+    IoErr(io::Error),
+    ParseIntError(num::ParseIntError),
+    /// The instruction at `addr` has an unknown opcode.
+    UnknownOpcode {
+        addr: usize,
+        opcode: usize,
+    },
+    /// The instruction at `addr` accessed memory past the end of the program.
+    OutOfBounds {
+        addr: usize,
+        index: usize,
+    },
+    /// The instruction at `addr` computed a value too large to store.
+    Overflow {
+        addr: usize,
+    },
+}
+
+impl error::Error for Error {}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::IoErr(e) => Display::fmt(e, f),
+            Error::ParseIntError(e) => Display::fmt(e, f),
+            Error::UnknownOpcode { addr, opcode } => {
+                write!(f, "unknown opcode {opcode} at address {addr}")
+            }
+            Error::OutOfBounds { addr, index } => write!(
+                f,
+                "instruction at address {addr} accessed out of bounds address {index}"
+            ),
+            Error::Overflow { addr } => write!(f, "overflow at address {addr}"),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(other: io::Error) -> Self {
+        Error::IoErr(other)
+    }
+}
+
+impl From<num::ParseIntError> for Error {
+    fn from(other: num::ParseIntError) -> Self {
+        Error::ParseIntError(other)
+    }
+}
@@ -1,3 +1,9 @@
+use std::ops::RangeInclusive;
+
+pub mod error;
+
+use error::Error;
+
 pub fn parse_input(input: &str) -> Result<Vec<usize>, std::num::ParseIntError> {
     input
         .split(',')
@@ -5,32 +11,88 @@ pub fn parse_input(input: &str) -> Result<Vec<usize>, std::num::ParseIntError> {
         .collect::<Result<Vec<usize>, std::num::ParseIntError>>()
 }
 
-#[must_use] pub fn run_prog(input: &[usize]) -> Vec<usize> {
+/// Runs the program, returning the final memory.
+///
+/// Returns an error with the address of the failing instruction if an opcode is unknown, memory
+/// past the end of the program is accessed, or a result overflows.
+pub fn run_prog(input: &[usize]) -> Result<Vec<usize>, Error> {
     let mut idx = 0;
     let mut output = Vec::with_capacity(input.len());
     output.extend_from_slice(input);
+
+    let read = |output: &[usize], addr: usize, index: usize| {
+        output
+            .get(index)
+            .copied()
+            .ok_or(Error::OutOfBounds { addr, index })
+    };
+
     loop {
-        match output[idx] {
-            1 => {
-                let operand_1 = output[output[idx + 1]];
-                let operand_2 = output[output[idx + 2]];
-                let store_idx = output[idx + 3];
-                output[store_idx] = operand_1 + operand_2;
-                idx += 4;
-            }
-            2 => {
-                let operand_1 = output[output[idx + 1]];
-                let operand_2 = output[output[idx + 2]];
-                let store_idx = output[idx + 3];
-                output[store_idx] = operand_1 * operand_2;
-                idx += 4;
-            }
+        let opcode = read(&output, idx, idx)?;
+        let op: fn(usize, usize) -> Option<usize> = match opcode {
+            1 => usize::checked_add,
+            2 => usize::checked_mul,
             99 => break,
-            _ => panic!("Unexpected operation"),
-        }
+            _ => return Err(Error::UnknownOpcode { addr: idx, opcode }),
+        };
+
+        let operand_1 = read(&output, idx, read(&output, idx, idx + 1)?)?;
+        let operand_2 = read(&output, idx, read(&output, idx, idx + 2)?)?;
+        let store_idx = read(&output, idx, idx + 3)?;
+        let value = op(operand_1, operand_2).ok_or(Error::Overflow { addr: idx })?;
+        *output.get_mut(store_idx).ok_or(Error::OutOfBounds {
+            addr: idx,
+            index: store_idx,
+        })? = value;
+        idx += 4;
+    }
+
+    Ok(output)
+}
+
+/// Finds the first noun and verb, searching `ranges.0` for the noun and `ranges.1` for the verb,
+/// for which the program leaves `target` at address 0.
+///
+/// A pair whose program faults is treated as not producing the target. Returns `Ok(None)` if no
+/// pair produces the target, or the first pair's error if every pair faults.
+pub fn find_noun_verb(
+    program: &[usize],
+    target: usize,
+    ranges: (RangeInclusive<usize>, RangeInclusive<usize>),
+) -> Result<Option<(usize, usize)>, Error> {
+    if program.len() < 3 {
+        return Err(Error::OutOfBounds {
+            addr: 0,
+            index: program.len(),
+        });
     }
 
-    output
+    let (nouns, verbs) = ranges;
+    let mut prog = program.to_vec();
+    let mut first_err = None;
+    let mut all_faulted = true;
+    for noun in nouns {
+        for verb in verbs.clone() {
+            prog[1] = noun;
+            prog[2] = verb;
+
+            match run_prog(&prog) {
+                Ok(output) => {
+                    if output[0] == target {
+                        return Ok(Some((noun, verb)));
+                    }
+                    all_faulted = false;
+                }
+                Err(e) => {
+                    first_err.get_or_insert(e);
+                }
+            }
+        }
+    }
+    match first_err {
+        Some(e) if all_faulted => Err(e),
+        _ => Ok(None),
+    }
 }
 
 #[cfg(test)]
@@ -40,7 +102,7 @@ mod tests {
     #[test]
     fn ex1() {
         let input = vec![1, 9, 10, 3, 2, 3, 11, 0, 99, 30, 40, 50];
-        let output = run_prog(&input);
+        let output = run_prog(&input).unwrap();
 
         assert_eq!(vec![3500, 9, 10, 70, 2, 3, 11, 0, 99, 30, 40, 50], output);
     }
@@ -48,7 +110,7 @@ mod tests {
     #[test]
     fn ex2() {
         let input = vec![1, 0, 0, 0, 99];
-        let output = run_prog(&input);
+        let output = run_prog(&input).unwrap();
 
         assert_eq!(vec![2, 0, 0, 0, 99], output);
     }
@@ -56,7 +118,7 @@ mod tests {
     #[test]
     fn ex3() {
         let input = vec![2, 3, 0, 3, 99];
-        let output = run_prog(&input);
+        let output = run_prog(&input).unwrap();
 
         assert_eq!(vec![2, 3, 0, 6, 99], output);
     }
@@ -64,7 +126,7 @@ mod tests {
     #[test]
     fn ex4() {
         let input = vec![2, 4, 4, 5, 99, 0];
-        let output = run_prog(&input);
+        let output = run_prog(&input).unwrap();
 
         assert_eq!(vec![2, 4, 4, 5, 99, 9801], output);
     }
@@ -72,8 +134,66 @@ mod tests {
     #[test]
     fn ex5() {
         let input = vec![1, 1, 1, 4, 99, 5, 6, 0, 99];
-        let output = run_prog(&input);
+        let output = run_prog(&input).unwrap();
 
         assert_eq!(vec![30, 1, 1, 4, 2, 5, 6, 0, 99], output);
     }
+
+    #[test]
+    fn errors() {
+        assert!(matches!(
+            run_prog(&[1, 0, 0, 0, 3]),
+            Err(Error::UnknownOpcode { addr: 4, opcode: 3 })
+        ));
+        assert!(matches!(
+            run_prog(&[1, 0, 9, 0, 99]),
+            Err(Error::OutOfBounds { addr: 0, index: 9 })
+        ));
+        assert!(matches!(
+            run_prog(&[1, 0, 0, 7, 99]),
+            Err(Error::OutOfBounds { addr: 0, index: 7 })
+        ));
+        assert!(matches!(
+            run_prog(&[1, 0, 0, 0]),
+            Err(Error::OutOfBounds { addr: 4, index: 4 })
+        ));
+        assert!(matches!(
+            run_prog(&[2, 0, 5, 0, 99, usize::MAX]),
+            Err(Error::Overflow { addr: 0 })
+        ));
+    }
+
+    #[test]
+    fn noun_verb_search() {
+        // Adds the values at the noun and verb addresses.
+        let program = vec![1, 0, 0, 0, 99, 10, 20, 30, 40];
+        assert_eq!(
+            Some((7, 8)),
+            find_noun_verb(&program, 70, (0..=8, 0..=8)).unwrap()
+        );
+        assert_eq!(
+            Some((5, 5)),
+            find_noun_verb(&program, 20, (5..=8, 5..=8)).unwrap()
+        );
+        assert_eq!(
+            None,
+            find_noun_verb(&program, 1000, (5..=8, 5..=8)).unwrap()
+        );
+        assert_eq!(
+            Some((7, 8)),
+            find_noun_verb(&program, 70, (0..=9, 0..=9)).unwrap()
+        );
+        assert_eq!(
+            None,
+            find_noun_verb(&program, 1000, (0..=9, 0..=9)).unwrap()
+        );
+        assert!(matches!(
+            find_noun_verb(&program, 70, (9..=10, 0..=9)),
+            Err(Error::OutOfBounds { addr: 0, index: 9 })
+        ));
+        assert!(matches!(
+            find_noun_verb(&[99], 0, (0..=1, 0..=1)),
+            Err(Error::OutOfBounds { .. })
+        ));
+    }
 }
@@ -1,32 +1,20 @@
 use std::io::{self};
+use std::process;
 
+use aoc_2019_2::error::Error;
 
-
-fn main() {
+fn main() -> Result<(), Error> {
     let mut input = String::new();
-    match io::stdin().read_line(&mut input) {
-        Ok(_) => match aoc_2019_2::parse_input(&input) {
-            Ok(mut prog) => {
-                'end: for noun in 0..=99 {
-                    for verb in 0..=99 {
-                        prog[1] = noun;
-                        prog[2] = verb;
-
-                        let output = aoc_2019_2::run_prog(&prog);
-                        if output[0] == 19_690_720 {
-                            break 'end;
-                        }
-                    }
-                }
+    io::stdin().read_line(&mut input)?;
+    let prog = aoc_2019_2::parse_input(&input)?;
 
-                println!("{}", 100 * prog[1] + prog[2]);
-            }
-            Err(e) => {
-                eprintln!("error: {e}");
-            }
-        },
-        Err(e) => {
-            eprintln!("error: {e}");
+    match aoc_2019_2::find_noun_verb(&prog, 19_690_720, (0..=99, 0..=99))? {
+        Some((noun, verb)) => println!("{}", 100 * noun + verb),
+        None => {
+            eprintln!("no noun and verb produce 19690720");
+            process::exit(1);
         }
     }
+
+    Ok(())
 }
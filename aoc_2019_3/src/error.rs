@@ -0,0 +1,61 @@
+use std::{
+    error,
+    fmt::{self, Debug, Display},
+    num,
+};
+
+/// Why a move is invalid.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Reason {
+    /// There is nothing between two commas.
+    EmptyToken,
+    /// The direction is not one of `U`, `D`, `L` or `R`.
+    UnknownDirection,
+    /// The text after the direction is not a length.
+    InvalidLength(num::ParseIntError),
+    /// The move takes the wire further from the origin, or further along the wire, than can be
+    /// represented.
+    TooFar,
+}
+
+impl Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reason::EmptyToken => f.write_str("empty move"),
+            Reason::UnknownDirection => f.write_str("unknown direction"),
+            Reason::InvalidLength(e) => write!(f, "invalid length: {e}"),
+            Reason::TooFar => f.write_str("wire goes too far"),
+        }
+    }
+}
+
+/// A move which could not be parsed or followed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseError {
+    /// The index of the wire, which is always 0 for a single wire.
+    pub wire: usize,
+    /// The index of the move in the wire.
+    pub index: usize,
+    /// The move's text.
+    pub token: String,
+    pub reason: Reason,
+}
+
+impl error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match &self.reason {
+            Reason::InvalidLength(e) => Some(e),
+            Reason::EmptyToken | Reason::UnknownDirection | Reason::TooFar => None,
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "wire {}, move {} ({:?}): {}",
+            self.wire, self.index, self.token, self.reason
+        )
+    }
+}
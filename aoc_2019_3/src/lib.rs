@@ -1,7 +1,11 @@
-use std::collections::BTreeSet;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::convert::TryFrom;
-use std::iter::FromIterator;
+use std::fmt;
+
+pub mod error;
+pub mod render;
+
+use error::{ParseError, Reason};
 
 #[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
 pub enum Move {
@@ -11,23 +15,52 @@ pub enum Move {
     Right(usize),
 }
 
-pub fn parse_moves(input: &str) -> Result<Vec<Move>, std::num::ParseIntError> {
-    input
-        .trim()
-        .split(',')
-        .map(|s| {
-            let s = s.trim();
-            let dir = &s[0..1];
-            let value = s[1..].parse::<usize>()?;
-            Ok(match dir {
-                "U" => Move::Up(value),
-                "D" => Move::Down(value),
-                "L" => Move::Left(value),
-                "R" => Move::Right(value),
-                _ => panic!(),
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Move::Up(len) => write!(f, "U{len}"),
+            Move::Down(len) => write!(f, "D{len}"),
+            Move::Left(len) => write!(f, "L{len}"),
+            Move::Right(len) => write!(f, "R{len}"),
+        }
+    }
+}
+
+/// Parses a wire's moves, separated by commas or, if there are no commas, by whitespace.
+///
+/// Directions may be uppercase or lowercase.
+pub fn parse_moves(input: &str) -> Result<Vec<Move>, ParseError> {
+    let tokens: Box<dyn Iterator<Item = &str>> = if input.contains(',') {
+        Box::new(input.trim().split(',').map(str::trim))
+    } else {
+        Box::new(input.split_whitespace())
+    };
+
+    tokens
+        .enumerate()
+        .map(|(index, token)| {
+            let error = |reason| ParseError {
+                wire: 0,
+                index,
+                token: token.to_string(),
+                reason,
+            };
+
+            let mut chars = token.chars();
+            let dir = chars.next().ok_or_else(|| error(Reason::EmptyToken))?;
+            let value = chars
+                .as_str()
+                .parse::<usize>()
+                .map_err(|e| error(Reason::InvalidLength(e)));
+            Ok(match dir.to_ascii_uppercase() {
+                'U' => Move::Up(value?),
+                'D' => Move::Down(value?),
+                'L' => Move::Left(value?),
+                'R' => Move::Right(value?),
+                _ => return Err(error(Reason::UnknownDirection)),
             })
         })
-        .collect::<Result<Vec<Move>, std::num::ParseIntError>>()
+        .collect()
 }
 
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, PartialOrd, Ord)]
@@ -38,7 +71,7 @@ pub struct Pos {
 
 impl Pos {
     #[must_use] pub fn manhattan_dist(&self) -> usize {
-        usize::try_from(self.x.abs()).unwrap() + usize::try_from(self.y.abs()).unwrap()
+        self.x.unsigned_abs() + self.y.unsigned_abs()
     }
 }
 
@@ -69,50 +102,361 @@ impl Pos {
     pos
 }
 
-pub fn find_closest_manhattan_dist(
-    input1: &str,
-    input2: &str,
-) -> Result<Option<usize>, std::num::ParseIntError> {
-    let moves1 = parse_moves(input1)?;
-    let moves2 = parse_moves(input2)?;
-    let visited_pos_with_steps1 = visited_pos_with_steps(&moves1);
-    let visited_pos_with_steps2 = visited_pos_with_steps(&moves2);
-    let visited_pos1 = BTreeSet::from_iter(visited_pos_with_steps1.keys());
-    let visited_pos2 = BTreeSet::from_iter(visited_pos_with_steps2.keys());
+/// A straight part of a wire.
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+pub struct Segment {
+    pub start: Pos,
+    pub end: Pos,
+    /// The steps along the wire to reach `start`.
+    pub steps: usize,
+}
+
+impl Segment {
+    fn min(&self) -> Pos {
+        Pos {
+            x: self.start.x.min(self.end.x),
+            y: self.start.y.min(self.end.y),
+        }
+    }
+
+    fn max(&self) -> Pos {
+        Pos {
+            x: self.start.x.max(self.end.x),
+            y: self.start.y.max(self.end.y),
+        }
+    }
+
+    /// True for a segment along a row, including a segment with no length.
+    fn is_horizontal(&self) -> bool {
+        self.start.y == self.end.y
+    }
+
+    /// The steps along the wire to reach a position on the segment.
+    fn steps_to(&self, p: Pos) -> usize {
+        self.steps + self.start.x.abs_diff(p.x) + self.start.y.abs_diff(p.y)
+    }
+}
+
+/// The segments of a wire starting at the origin.
+///
+/// Returns `Reason::TooFar` for the first move which ends at a position, or after a number of
+/// steps, which does not fit.
+pub fn segments(moves: &[Move]) -> Result<Vec<Segment>, ParseError> {
+    let mut start = Pos { x: 0, y: 0 };
+    let mut steps = 0_usize;
+    moves
+        .iter()
+        .enumerate()
+        .map(|(index, m)| {
+            let (dx, dy, len) = match *m {
+                Move::Up(len) => (0, 1, len),
+                Move::Down(len) => (0, -1, len),
+                Move::Left(len) => (-1, 0, len),
+                Move::Right(len) => (1, 0, len),
+            };
+            let too_far = || ParseError {
+                wire: 0,
+                index,
+                token: m.to_string(),
+                reason: Reason::TooFar,
+            };
+            let len_i = isize::try_from(len).map_err(|_| too_far())?;
+            let end = Pos {
+                x: start.x.checked_add(dx * len_i).ok_or_else(too_far)?,
+                y: start.y.checked_add(dy * len_i).ok_or_else(too_far)?,
+            };
+            let segment = Segment { start, end, steps };
+            start = end;
+            steps = steps.checked_add(len).ok_or_else(too_far)?;
+            Ok(segment)
+        })
+        .collect()
+}
+
+/// Parses one wire per line, ignoring blank lines, and splits each into segments.
+pub fn parse_wire_segments(input: &str) -> Result<Vec<Vec<Segment>>, ParseError> {
+    parse_wires(input)?
+        .iter()
+        .enumerate()
+        .map(|(wire, moves)| segments(moves).map_err(|e| ParseError { wire, ..e }))
+        .collect()
+}
+
+/// Parses one wire per line, ignoring blank lines.
+pub fn parse_wires(input: &str) -> Result<Vec<Vec<Move>>, ParseError> {
+    input
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .enumerate()
+        .map(|(wire, line)| parse_moves(line).map_err(|e| ParseError { wire, ..e }))
+        .collect()
+}
+
+/// Widens a coordinate so that negating it or stepping past it cannot overflow.
+pub(crate) fn wide(n: isize) -> i128 {
+    i128::try_from(n).expect("isize to fit in i128")
+}
+
+/// Positions along a row or column, from `min` to `max`, where the same segments of two or more
+/// wires meet.
+///
+/// A single position has `min` equal to `max`. Wires which run along each other meet at every
+/// position of the overlap.
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub struct Intersection {
+    pub min: Pos,
+    pub max: Pos,
+    /// The index of each wire with its segments through the positions, ordered by wire and then
+    /// by steps.
+    pub segments: Vec<(usize, Segment)>,
+}
+
+impl Intersection {
+    /// The index of each wire which meets here, in order.
+    #[must_use]
+    pub fn wires(&self) -> Vec<usize> {
+        let mut wires = self
+            .segments
+            .iter()
+            .map(|&(wire, _)| wire)
+            .collect::<Vec<_>>();
+        wires.dedup();
+        wires
+    }
+
+    /// Every position from `min` to `max`.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let (min, max) = (self.min, self.max);
+        (min.x..=max.x).flat_map(move |x| (min.y..=max.y).map(move |y| Pos { x, y }))
+    }
+
+    /// The fewest steps each wire takes to reach one of the positions, in wire order.
+    #[must_use]
+    pub fn steps_to(&self, p: Pos) -> Vec<(usize, usize)> {
+        let mut steps = Vec::<(usize, usize)>::new();
+        for &(wire, segment) in &self.segments {
+            let to_p = segment.steps_to(p);
+            match steps.last_mut() {
+                Some((last, fewest)) if *last == wire => *fewest = (*fewest).min(to_p),
+                _ => steps.push((wire, to_p)),
+            }
+        }
+        steps
+    }
+
+    /// The sum of the steps each wire takes to reach one of the positions.
+    ///
+    /// Each wire's steps fit in a `usize`, but their sum may not.
+    #[must_use]
+    pub fn total_steps_to(&self, p: Pos) -> u128 {
+        self.steps_to(p)
+            .iter()
+            .map(|&(_, steps)| u128::try_from(steps).expect("usize to fit in u128"))
+            .sum()
+    }
+
+    /// The position the wires reach in the fewest combined steps, with the steps.
+    ///
+    /// Each segment's steps rise or fall steadily from `min` to `max`, so neither the fewest
+    /// steps of a wire nor their sum can dip between the two ends.
+    #[must_use]
+    pub fn fewest_steps(&self) -> (Pos, u128) {
+        let (at_min, at_max) = (self.total_steps_to(self.min), self.total_steps_to(self.max));
+        if at_max < at_min {
+            (self.max, at_max)
+        } else {
+            (self.min, at_min)
+        }
+    }
+
+    /// The position closest to the origin.
+    #[must_use]
+    pub fn closest(&self) -> Pos {
+        Pos {
+            x: 0_isize.clamp(self.min.x, self.max.x),
+            y: 0_isize.clamp(self.min.y, self.max.y),
+        }
+    }
+}
+
+/// A wire's segment in a row or column, running from `lo` to `hi` along it, or crossing it where
+/// the two are equal.
+#[derive(Clone, Copy, Debug)]
+struct Cover {
+    wire: usize,
+    segment: Segment,
+    lo: i128,
+    hi: i128,
+}
+
+/// Splits a row, or a column, into runs where the same segments meet, and adds each run where
+/// two or more wires meet to `found`.
+///
+/// Rows report the runs with a horizontal segment and columns report the rest, so each position
+/// is reported once. The origin is a run of its own, where the segments starting there are left
+/// out.
+fn sweep(line: isize, horizontal: bool, covers: &[Cover], found: &mut Vec<Intersection>) {
+    let mut changes = BTreeMap::<i128, Vec<(usize, bool)>>::new();
+    for (i, c) in covers.iter().enumerate() {
+        changes.entry(c.lo).or_default().push((i, true));
+        changes.entry(c.hi + 1).or_default().push((i, false));
+    }
+    if line == 0 {
+        changes.entry(0).or_default();
+        changes.entry(1).or_default();
+    }
+
+    let pos = |along: i128| {
+        let along = isize::try_from(along).expect("position on a segment to fit in isize");
+        if horizontal {
+            Pos { x: along, y: line }
+        } else {
+            Pos { x: line, y: along }
+        }
+    };
+    let mut active = BTreeSet::new();
+    let mut changes = changes.into_iter().peekable();
+    while let Some((lo, starts)) = changes.next() {
+        for (i, start) in starts {
+            if start {
+                active.insert(i);
+            } else {
+                active.remove(&i);
+            }
+        }
+        let hi = match changes.peek() {
+            Some(&(next, _)) => next - 1,
+            None => break,
+        };
+        if active.is_empty()
+            || active.iter().any(|&i| covers[i].segment.is_horizontal()) != horizontal
+        {
+            continue;
+        }
 
-    let intersection: BTreeSet<_> = visited_pos1.intersection(&visited_pos2).copied().collect();
-    Ok(intersection.into_iter().map(|&p| p.manhattan_dist()).min())
+        let (min, max) = (pos(lo), pos(hi));
+        let mut segments = active
+            .iter()
+            .map(|&i| (covers[i].wire, covers[i].segment))
+            .filter(|(_, s)| s.steps_to(min) > 0 && s.steps_to(max) > 0)
+            .collect::<Vec<_>>();
+        segments.sort_by_key(|&(wire, s)| (wire, s.steps));
+        if segments.windows(2).any(|w| w[0].0 != w[1].0) {
+            found.push(Intersection { min, max, segments });
+        }
+    }
 }
 
-pub fn find_fewest_steps(
+/// Every run of positions where two or more of the wires meet, ordered by position.
+///
+/// Wires which run along each other give one intersection for the whole overlap, split only
+/// where the segments meeting there change. The origin is not reported unless a wire returns to
+/// it.
+#[must_use]
+pub fn intersections(wires: &[Vec<Segment>]) -> Vec<Intersection> {
+    let mut rows = BTreeMap::<isize, Vec<Cover>>::new();
+    let mut columns = BTreeMap::<isize, Vec<Cover>>::new();
+    for (wire, segments) in wires.iter().enumerate() {
+        for &segment in segments {
+            let (min, max) = (segment.min(), segment.max());
+            if segment.is_horizontal() {
+                rows.entry(min.y).or_default().push(Cover {
+                    wire,
+                    segment,
+                    lo: wide(min.x),
+                    hi: wide(max.x),
+                });
+            } else {
+                columns.entry(min.x).or_default().push(Cover {
+                    wire,
+                    segment,
+                    lo: wide(min.y),
+                    hi: wide(max.y),
+                });
+            }
+        }
+    }
+
+    // Each segment also crosses the lines in the other direction which it spans.
+    let mut crossings = Vec::new();
+    for (&x, covers) in &columns {
+        for c in covers {
+            let (bottom, top) = (c.segment.min().y, c.segment.max().y);
+            for &y in rows.range(bottom..=top).map(|(y, _)| y) {
+                let at = Cover {
+                    lo: wide(x),
+                    hi: wide(x),
+                    ..*c
+                };
+                crossings.push((true, y, at));
+            }
+        }
+    }
+    for (&y, covers) in &rows {
+        for c in covers {
+            let (left, right) = (c.segment.min().x, c.segment.max().x);
+            for &x in columns.range(left..=right).map(|(x, _)| x) {
+                let at = Cover {
+                    lo: wide(y),
+                    hi: wide(y),
+                    ..*c
+                };
+                crossings.push((false, x, at));
+            }
+        }
+    }
+    for (horizontal, line, c) in crossings {
+        let lines = if horizontal { &mut rows } else { &mut columns };
+        lines.entry(line).or_default().push(c);
+    }
+
+    let mut found = Vec::new();
+    for (&y, covers) in &rows {
+        sweep(y, true, covers, &mut found);
+    }
+    for (&x, covers) in &columns {
+        sweep(x, false, covers, &mut found);
+    }
+    found.sort_by_key(|i| (i.min, i.max));
+    found
+}
+
+/// The runs of positions where at least `k` of the wires meet, ordered by position.
+///
+/// Values of `k` below 2 return every intersection.
+#[must_use]
+pub fn intersections_of_at_least(wires: &[Vec<Segment>], k: usize) -> Vec<Intersection> {
+    let mut found = intersections(wires);
+    found.retain(|i| i.wires().len() >= k);
+    found
+}
+
+pub fn find_closest_manhattan_dist(
     input1: &str,
     input2: &str,
-) -> Result<Option<usize>, std::num::ParseIntError> {
-    let moves1 = parse_moves(input1)?;
-    let moves2 = parse_moves(input2)?;
-    let visited_pos_with_steps1 = visited_pos_with_steps(&moves1);
-    let visited_pos_with_steps2 = visited_pos_with_steps(&moves2);
-    let visited_pos1 = BTreeSet::from_iter(visited_pos_with_steps1.keys());
-    let visited_pos2 = BTreeSet::from_iter(visited_pos_with_steps2.keys());
-
-    let intersection: BTreeSet<_> = visited_pos1.intersection(&visited_pos2).copied().collect();
-    Ok(intersection
-        .into_iter()
-        .filter_map(|p| {
-            match (
-                visited_pos_with_steps1.get(p),
-                visited_pos_with_steps2.get(p),
-            ) {
-                (Some(dist1), Some(dist2)) => Some(dist1 + dist2),
-                _ => None,
-            }
-        })
+) -> Result<Option<usize>, ParseError> {
+    let wire1 = segments(&parse_moves(input1)?)?;
+    let wire2 = segments(&parse_moves(input2)?)?;
+    Ok(intersections(&[wire1, wire2])
+        .iter()
+        .map(|i| i.closest().manhattan_dist())
+        .min())
+}
+
+pub fn find_fewest_steps(input1: &str, input2: &str) -> Result<Option<u128>, ParseError> {
+    let wire1 = segments(&parse_moves(input1)?)?;
+    let wire2 = segments(&parse_moves(input2)?)?;
+    Ok(intersections(&[wire1, wire2])
+        .iter()
+        .map(|i| i.fewest_steps().1)
         .min())
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use std::collections::BTreeSet;
+    use std::iter::FromIterator;
 
     #[test]
     fn parse() {
@@ -187,4 +531,290 @@ mod test {
         let input2 = "U98,R91,D20,R16,D67,R40,U7,R15,U6,R7";
         assert_eq!(find_fewest_steps(input1, input2), Ok(Some(410)));
     }
+
+    #[test]
+    fn segment_steps() {
+        let wire = segments(&parse_moves("R8,U5,L5,D3").unwrap()).unwrap();
+        assert_eq!(
+            Segment {
+                start: Pos { x: 8, y: 5 },
+                end: Pos { x: 3, y: 5 },
+                steps: 13,
+            },
+            wire[2]
+        );
+        assert_eq!(15, wire[2].steps_to(Pos { x: 6, y: 5 }));
+    }
+
+    #[test]
+    fn overlapping_and_revisited() {
+        // The wires run along each other, and the second wire crosses itself at the first
+        // wire's path.
+        let input1 = "R10";
+        let input2 = "U2,R3,D4,L1,U2,R5";
+        assert_eq!(find_closest_manhattan_dist(input1, input2), Ok(Some(2)));
+        assert_eq!(find_fewest_steps(input1, input2), Ok(Some(10)));
+
+        let moves1 = parse_moves(input1).unwrap();
+        let moves2 = parse_moves(input2).unwrap();
+        let visited1 = visited_pos_with_steps(&moves1);
+        let visited2 = visited_pos_with_steps(&moves2);
+        let expected = visited1
+            .iter()
+            .filter_map(|(p, s1)| Some((*p, u128::try_from(s1 + visited2.get(p)?).unwrap())))
+            .collect::<HashMap<_, _>>();
+        let wires = [segments(&moves1).unwrap(), segments(&moves2).unwrap()];
+        let found = intersections(&wires);
+        let mut actual = HashMap::new();
+        for i in &found {
+            for p in i.positions() {
+                assert_eq!(None, actual.insert(p, i.total_steps_to(p)));
+            }
+        }
+        assert_eq!(expected, actual);
+
+        // The overlap is split only where the second wire crosses itself.
+        assert_eq!(
+            vec![((2, 0), (2, 0)), ((3, 0), (3, 0)), ((4, 0), (7, 0))],
+            found
+                .iter()
+                .map(|i| ((i.min.x, i.min.y), (i.max.x, i.max.y)))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn matches_every_position() {
+        // Short wires which often run along and back over each other, checked against every
+        // position they visit.
+        let mut seed = 1_u64;
+        let mut next = |n: u64| {
+            seed = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+            (seed >> 33) % n
+        };
+        for _ in 0..300 {
+            let moves = (0..3)
+                .map(|_| {
+                    (0..8)
+                        .map(|_| {
+                            let len = usize::try_from(next(5)).unwrap();
+                            match next(4) {
+                                0 => Move::Up(len),
+                                1 => Move::Down(len),
+                                2 => Move::Left(len),
+                                _ => Move::Right(len),
+                            }
+                        })
+                        .collect::<Vec<_>>()
+                })
+                .collect::<Vec<_>>();
+
+            let mut expected = HashMap::<Pos, Vec<(usize, usize)>>::new();
+            for (wire, m) in moves.iter().enumerate() {
+                for (p, steps) in visited_pos_with_steps(m) {
+                    expected.entry(p).or_default().push((wire, steps));
+                }
+            }
+            expected.retain(|_, wires| wires.len() >= 2);
+
+            let wires = moves
+                .iter()
+                .map(|m| segments(m).unwrap())
+                .collect::<Vec<_>>();
+            let mut actual = HashMap::new();
+            for i in intersections(&wires) {
+                let positions = i.positions().collect::<Vec<_>>();
+                for &p in &positions {
+                    assert_eq!(None, actual.insert(p, i.steps_to(p)), "{:?}", moves);
+                }
+                let fewest = positions.iter().map(|&p| i.total_steps_to(p)).min();
+                assert_eq!(fewest, Some(i.fewest_steps().1), "{:?}", moves);
+                assert_eq!(i.total_steps_to(i.fewest_steps().0), i.fewest_steps().1);
+                let closest = positions.iter().map(Pos::manhattan_dist).min();
+                assert_eq!(closest, Some(i.closest().manhattan_dist()), "{:?}", moves);
+            }
+            assert_eq!(expected, actual, "{:?}", moves);
+        }
+    }
+
+    #[test]
+    fn many_wires() {
+        // The third wire runs along the second wire from (6, 3) to (6, 6).
+        let wires = parse_wire_segments("R8,U5,L5,D3\n\nU7,R6,D4,L4\nD1,R6,U7\n").unwrap();
+        assert_eq!(3, wires.len());
+
+        let found = intersections(&wires);
+        assert_eq!(
+            vec![
+                ((3, 3), (3, 3)),
+                ((6, 0), (6, 0)),
+                ((6, 3), (6, 3)),
+                ((6, 4), (6, 4)),
+                ((6, 5), (6, 5)),
+                ((6, 6), (6, 6))
+            ],
+            found
+                .iter()
+                .map(|i| ((i.min.x, i.min.y), (i.max.x, i.max.y)))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(vec![1, 2], found[2].wires());
+        assert_eq!(
+            vec![(1, 17), (2, 11)],
+            found[2].steps_to(Pos { x: 6, y: 3 })
+        );
+        assert_eq!((Pos { x: 6, y: 3 }, 28), found[2].fewest_steps());
+        assert_eq!(40, found[0].total_steps_to(Pos { x: 3, y: 3 }));
+
+        let three = intersections_of_at_least(&wires, 3);
+        assert_eq!(1, three.len());
+        assert_eq!(
+            (Pos { x: 6, y: 5 }, Pos { x: 6, y: 5 }),
+            (three[0].min, three[0].max)
+        );
+        assert_eq!(
+            vec![(0, 15), (1, 15), (2, 13)],
+            three[0].steps_to(Pos { x: 6, y: 5 })
+        );
+        assert!(intersections_of_at_least(&wires, 4).is_empty());
+        assert_eq!(found, intersections_of_at_least(&wires, 0));
+    }
+
+    #[test]
+    fn parse_variants() {
+        let expected = Ok(vec![Move::Up(12), Move::Down(3), Move::Left(0)]);
+        assert_eq!(expected, parse_moves("u12,D3,l0"));
+        assert_eq!(expected, parse_moves(" U12, d3 ,L0\n"));
+        assert_eq!(expected, parse_moves("U12 D3\n\tL0"));
+        assert_eq!(Ok(vec![]), parse_moves(""));
+    }
+
+    #[test]
+    fn parse_errors() {
+        let error = |index, token: &str, reason| {
+            Err(ParseError {
+                wire: 0,
+                index,
+                token: token.to_string(),
+                reason,
+            })
+        };
+        assert_eq!(error(1, "", Reason::EmptyToken), parse_moves("U1,,D2"));
+        assert_eq!(error(2, "", Reason::EmptyToken), parse_moves("U1,D2,"));
+        assert_eq!(
+            error(1, "X3", Reason::UnknownDirection),
+            parse_moves("U1 X3")
+        );
+        assert_eq!(error(0, "é3", Reason::UnknownDirection), parse_moves("é3"));
+        assert!(matches!(
+            parse_moves("U1,R,D2"),
+            Err(ParseError {
+                index: 1,
+                reason: Reason::InvalidLength(_),
+                ..
+            })
+        ));
+        assert!(matches!(
+            parse_moves("U1,R-2"),
+            Err(ParseError {
+                index: 1,
+                reason: Reason::InvalidLength(_),
+                ..
+            })
+        ));
+
+        let e = parse_wires("U1,R2\n\nD1,Q2\n").unwrap_err();
+        assert_eq!((1, 1), (e.wire, e.index));
+        assert_eq!("wire 1, move 1 (\"Q2\"): unknown direction", e.to_string());
+    }
+
+    #[test]
+    fn long_wires() {
+        // The same wires as the small ones, with every move half a million times longer, so
+        // visiting every position would take millions of steps per wire.
+        let small1 = "R2,U2,L6,D6";
+        let small2 = "D1,L5,U2,R7";
+        assert_eq!(find_closest_manhattan_dist(small1, small2), Ok(Some(3)));
+        assert_eq!(find_fewest_steps(small1, small2), Ok(Some(18)));
+
+        let input1 = "R1000000,U1000000,L3000000,D3000000";
+        let input2 = "D500000,L2500000,U1000000,R3500000";
+        assert_eq!(
+            find_closest_manhattan_dist(input1, input2),
+            Ok(Some(1_500_000))
+        );
+        assert_eq!(find_fewest_steps(input1, input2), Ok(Some(9_000_000)));
+
+        let wires = parse_wire_segments(&format!("{input1}\n{input2}\n")).unwrap();
+        assert_eq!(
+            vec![
+                (-2_000_000, -500_000),
+                (-2_000_000, 500_000),
+                (1_000_000, 500_000)
+            ],
+            intersections(&wires)
+                .iter()
+                .map(|i| (i.min.x, i.min.y))
+                .collect::<Vec<_>>()
+        );
+
+        // The wires run along each other for a million positions.
+        let input1 = "R1000000,U1000000";
+        let input2 = "U1,R1,D1,R2000000";
+        assert_eq!(find_closest_manhattan_dist(input1, input2), Ok(Some(1)));
+        assert_eq!(find_fewest_steps(input1, input2), Ok(Some(4)));
+
+        let wires = parse_wire_segments(&format!("{input1}\n{input2}\n")).unwrap();
+        let found = intersections(&wires);
+        assert_eq!(
+            vec![
+                ((1, 0), (1, 0)),
+                ((2, 0), (999_999, 0)),
+                ((1_000_000, 0), (1_000_000, 0)),
+            ],
+            found
+                .iter()
+                .map(|i| ((i.min.x, i.min.y), (i.max.x, i.max.y)))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(0, 999_999), (1, 1_000_001)],
+            found[1].steps_to(Pos { x: 999_999, y: 0 })
+        );
+        assert_eq!((Pos { x: 2, y: 0 }, 6), found[1].fewest_steps());
+    }
+
+    #[test]
+    fn wires_which_go_too_far() {
+        let max = isize::MAX;
+        let e = segments(&parse_moves(&format!("R{max},U1,R1")).unwrap()).unwrap_err();
+        assert_eq!(
+            (2, "R1", Reason::TooFar),
+            (e.index, e.token.as_str(), e.reason)
+        );
+
+        // The positions fit, but the steps along the wire do not.
+        let moves = parse_moves(&format!("R{max},L{max},L2")).unwrap();
+        assert_eq!(2, segments(&moves).unwrap_err().index);
+
+        // Each wire's steps fit, but not their sum.
+        let m = isize::MAX - 1;
+        assert_eq!(
+            Ok(Some(2 * u128::try_from(m).unwrap() + 4)),
+            find_fewest_steps(&format!("U1,R{m},L{m},D1"), &format!("D1,R{m},L{m},U2"))
+        );
+
+        let too_long = format!("U{}", usize::MAX);
+        assert_eq!(
+            Reason::TooFar,
+            find_fewest_steps("R1", &too_long).unwrap_err().reason
+        );
+
+        let e = parse_wire_segments(&format!("R1\n{too_long}\n")).unwrap_err();
+        assert_eq!((1, 0), (e.wire, e.index));
+        assert_eq!(
+            format!("wire 1, move 0 (\"{too_long}\"): wire goes too far"),
+            e.to_string()
+        );
+    }
 }
@@ -1,38 +1,52 @@
-use std::io::{self};
-
-
+use std::env;
+use std::io::{self, Read};
 
+/// Reads one wire per line from stdin and prints the fewest combined steps the wires take to
+/// reach an intersection.
+///
+/// `--svg` prints the wires as an SVG image instead, and `--ascii` prints them as text fitting
+/// the terminal.
 fn main() {
-    let mut input1 = String::new();
-    match io::stdin().read_line(&mut input1) {
-        Ok(_) => {}
-        Err(e) => {
-            eprintln!("error: {e}");
-            std::process::exit(1);
-        }
-    };
+    let arg = env::args().nth(1);
+    if !matches!(arg.as_deref(), None | Some("--svg") | Some("--ascii")) {
+        eprintln!("usage: aoc_2019_3 [--svg | --ascii] < input");
+        std::process::exit(2);
+    }
 
-    let mut input2 = String::new();
-    match io::stdin().read_line(&mut input2) {
-        Ok(_) => {}
+    let mut input = String::new();
+    if let Err(e) = io::stdin().read_to_string(&mut input) {
+        eprintln!("error: {e}");
+        std::process::exit(1);
+    }
+
+    let wires = match aoc_2019_3::parse_wire_segments(&input) {
+        Ok(wires) => wires,
         Err(e) => {
             eprintln!("error: {e}");
             std::process::exit(1);
         }
     };
 
-    match aoc_2019_3::find_fewest_steps(&input1, &input2) {
-        Ok(fewest_steps) => {
-            if let Some(fewest_steps) = fewest_steps {
-                println!("{fewest_steps}");
-            } else {
-                eprintln!("could not find the fewest steps");
-                std::process::exit(1);
-            }
+    match arg.as_deref() {
+        Some("--svg") => {
+            print!("{}", aoc_2019_3::render::to_svg(&wires));
+            return;
         }
-        Err(e) => {
-            eprintln!("error: {e}");
-            std::process::exit(1);
+        Some("--ascii") => {
+            print!("{}", aoc_2019_3::render::to_ascii(&wires, 100, 50));
+            return;
         }
+        _ => {}
+    }
+
+    let fewest_steps = aoc_2019_3::intersections(&wires)
+        .iter()
+        .map(|i| i.fewest_steps().1)
+        .min();
+    if let Some(fewest_steps) = fewest_steps {
+        println!("{fewest_steps}");
+    } else {
+        eprintln!("could not find the fewest steps");
+        std::process::exit(1);
     }
 }
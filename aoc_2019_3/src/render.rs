@@ -0,0 +1,278 @@
+//! Draws wires as SVG or as ASCII for the terminal.
+//!
+//! Up is toward the top in both renderings.
+
+use std::convert::TryFrom;
+use std::fmt::Write;
+
+use aoc_grid::{Grid, Point2};
+
+use crate::{intersections, wide, Intersection, Pos, Segment};
+
+/// Stroke colors for the wires, reused if there are more wires than colors.
+const COLORS: &[&str] = &[
+    "#e41a1c", "#377eb8", "#4daf4a", "#984ea3", "#ff7f00", "#a65628", "#f781bf",
+];
+
+/// The smallest and largest positions of the wires and the origin.
+fn bounds(wires: &[Vec<Segment>]) -> (Pos, Pos) {
+    let origin = Pos { x: 0, y: 0 };
+    wires
+        .iter()
+        .flatten()
+        .flat_map(|s| vec![s.start, s.end])
+        .fold((origin, origin), |(min, max), p| {
+            (
+                Pos {
+                    x: min.x.min(p.x),
+                    y: min.y.min(p.y),
+                },
+                Pos {
+                    x: max.x.max(p.x),
+                    y: max.y.max(p.y),
+                },
+            )
+        })
+}
+
+/// The position the wires reach in the fewest combined steps.
+fn best(found: &[Intersection]) -> Option<Pos> {
+    found
+        .iter()
+        .map(Intersection::fewest_steps)
+        .min_by_key(|&(_, steps)| steps)
+        .map(|(p, _)| p)
+}
+
+/// The cells from `from` to `to` along a row or column.
+fn line(from: Point2, to: Point2) -> impl Iterator<Item = Point2> {
+    let dir = Point2::new((to.x - from.x).signum(), (to.y - from.y).signum());
+    let len = (to.x - from.x).abs().max((to.y - from.y).abs());
+    (0..=len).map(move |n| from + dir * n)
+}
+
+/// Renders the wires as an SVG image with each wire in a different color.
+///
+/// Intersections are circled, overlaps where wires run along each other are drawn in thick
+/// black, the position with the fewest combined steps is filled in red, and the origin is
+/// filled in black.
+#[must_use]
+pub fn to_svg(wires: &[Vec<Segment>]) -> String {
+    let (min, max) = bounds(wires);
+    let (min_x, min_y, max_x, max_y) = (wide(min.x), wide(min.y), wide(max.x), wide(max.y));
+    let extent = (max_x - min_x).max(max_y - min_y);
+    let r = (extent / 100).max(1);
+    let margin = 2 * r;
+
+    let mut svg = String::new();
+    writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">",
+        min_x - margin,
+        -max_y - margin,
+        max_x - min_x + 2 * margin,
+        max_y - min_y + 2 * margin
+    )
+    .expect("writing to a string to succeed");
+
+    for (i, wire) in wires.iter().enumerate() {
+        let points = wire
+            .first()
+            .map(|s| s.start)
+            .into_iter()
+            .chain(wire.iter().map(|s| s.end))
+            .map(|p| format!("{},{}", p.x, -wide(p.y)))
+            .collect::<Vec<_>>()
+            .join(" ");
+        writeln!(
+            svg,
+            "<polyline class=\"wire\" points=\"{}\" fill=\"none\" stroke=\"{}\" \
+             stroke-width=\"1\" vector-effect=\"non-scaling-stroke\"/>",
+            points,
+            COLORS[i % COLORS.len()]
+        )
+        .expect("writing to a string to succeed");
+    }
+
+    let found = intersections(wires);
+    let best = best(&found);
+    let circles = found
+        .iter()
+        .filter(|i| i.min == i.max && Some(i.min) != best)
+        .map(|i| ("intersection", i.min, "none"))
+        .chain(best.map(|p| ("best", p, "red")));
+    for i in found.iter().filter(|i| i.min != i.max) {
+        writeln!(
+            svg,
+            "<line class=\"overlap\" x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"black\" \
+             stroke-width=\"3\" vector-effect=\"non-scaling-stroke\"/>",
+            i.min.x,
+            -wide(i.min.y),
+            i.max.x,
+            -wide(i.max.y)
+        )
+        .expect("writing to a string to succeed");
+    }
+    for (class, p, fill) in circles {
+        writeln!(
+            svg,
+            "<circle class=\"{}\" cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\" stroke=\"black\" \
+             vector-effect=\"non-scaling-stroke\"/>",
+            class,
+            p.x,
+            -wide(p.y),
+            r,
+            fill
+        )
+        .expect("writing to a string to succeed");
+    }
+    writeln!(
+        svg,
+        "<circle class=\"origin\" cx=\"0\" cy=\"0\" r=\"{r}\" fill=\"black\"/>"
+    )
+    .expect("writing to a string to succeed");
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+enum Cell {
+    Empty,
+    Wire(usize),
+    Wires,
+}
+
+/// Renders the wires as text no larger than `max_width` by `max_height` characters.
+///
+/// Each character covers a square of positions, scaled down as needed to fit. A wire is drawn
+/// with its index (`0`-`9`, then letters), cells with more than one wire are `+`, intersections
+/// are `X`, the intersection with the fewest combined steps is `*` and the origin is `o`.
+#[must_use]
+pub fn to_ascii(wires: &[Vec<Segment>], max_width: usize, max_height: usize) -> String {
+    let (min, max) = bounds(wires);
+    let span = |lo: isize, hi: isize| lo.abs_diff(hi).saturating_add(1);
+    let scale = (span(min.x, max.x).div_ceil(max_width.max(1)))
+        .max(span(min.y, max.y).div_ceil(max_height.max(1)))
+        .max(1);
+
+    // Cells are counted from the top left, with the largest `y` in row 0.
+    let cell = |p: Pos| {
+        Point2::new(
+            i64::try_from(p.x.abs_diff(min.x) / scale).expect("column to fit in i64"),
+            i64::try_from(max.y.abs_diff(p.y) / scale).expect("row to fit in i64"),
+        )
+    };
+    let size = cell(Pos { x: max.x, y: min.y });
+    let mut canvas = Grid::new(
+        usize::try_from(size.x + 1).expect("width to be positive"),
+        usize::try_from(size.y + 1).expect("height to be positive"),
+        Cell::Empty,
+    );
+
+    for (i, wire) in wires.iter().enumerate() {
+        for s in wire {
+            for p in line(cell(s.start), cell(s.end)) {
+                let c = &mut canvas[p];
+                *c = match *c {
+                    Cell::Empty => Cell::Wire(i),
+                    Cell::Wire(j) if j == i => Cell::Wire(i),
+                    Cell::Wire(_) | Cell::Wires => Cell::Wires,
+                };
+            }
+        }
+    }
+
+    let mut chars = canvas.map(|c| match c {
+        Cell::Empty => '.',
+        Cell::Wire(i) => std::char::from_digit(u32::try_from(i % 36).unwrap(), 36).unwrap(),
+        Cell::Wires => '+',
+    });
+    let found = intersections(wires);
+    for i in &found {
+        for p in line(cell(i.min), cell(i.max)) {
+            chars[p] = 'X';
+        }
+    }
+    if let Some(best) = best(&found) {
+        chars[cell(best)] = '*';
+    }
+    chars[cell(Pos { x: 0, y: 0 })] = 'o';
+    chars.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_moves, segments};
+
+    fn example() -> Vec<Vec<Segment>> {
+        vec![
+            segments(&parse_moves("R8,U5,L5,D3").unwrap()).unwrap(),
+            segments(&parse_moves("U7,R6,D4,L4").unwrap()).unwrap(),
+        ]
+    }
+
+    #[test]
+    fn svg() {
+        let svg = to_svg(&example());
+        assert!(
+            svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"-2 -9 12 11\">\n")
+        );
+        assert!(svg.contains("points=\"0,0 8,0 8,-5 3,-5 3,-2\" fill=\"none\" stroke=\"#e41a1c\""));
+        assert!(svg.contains("points=\"0,0 0,-7 6,-7 6,-3 2,-3\" fill=\"none\" stroke=\"#377eb8\""));
+        assert!(svg.contains("<circle class=\"intersection\" cx=\"3\" cy=\"-3\""));
+        assert!(svg.contains("<circle class=\"best\" cx=\"6\" cy=\"-5\""));
+        assert!(svg.ends_with("</svg>\n"));
+    }
+
+    #[test]
+    fn ascii() {
+        let expected = [
+            "1111111..",
+            "1.....1..",
+            "1..000*00",
+            "1..0..1.0",
+            "1.1X111.0",
+            "1..0....0",
+            "1.......0",
+            "o00000000",
+            "",
+        ];
+        assert_eq!(expected.join("\n"), to_ascii(&example(), 80, 40));
+
+        let expected = ["1111.", "100*0", "1X110", "o0000", ""];
+        assert_eq!(expected.join("\n"), to_ascii(&example(), 5, 5));
+    }
+
+    #[test]
+    fn overlap() {
+        let wires = crate::parse_wire_segments("R4\nU1,R1,D1,R4\n").unwrap();
+        let svg = to_svg(&wires);
+        assert!(svg.contains("<line class=\"overlap\" x1=\"2\" y1=\"0\" x2=\"4\" y2=\"0\""));
+        assert!(svg.contains("<circle class=\"best\" cx=\"1\" cy=\"0\""));
+
+        let expected = ["11....", "o*XXX1", ""];
+        assert_eq!(expected.join("\n"), to_ascii(&wires, 80, 40));
+    }
+
+    #[test]
+    fn ascii_fits_negative_coordinates() {
+        let wires = |input: &str| crate::parse_wire_segments(input).unwrap();
+
+        let expected = ["1....", "1....", "1....", "1....", "o0000", ""];
+        assert_eq!(expected.join("\n"), to_ascii(&wires("L1,R9\nU9\n"), 5, 5));
+
+        let wires = wires("D21,L33,U47,R5\nL11,D30,R52,U3\nU7,L40,D9\n");
+        for &(max_width, max_height) in &[(1, 1), (5, 5), (7, 4), (10, 30), (80, 40)] {
+            let ascii = to_ascii(&wires, max_width, max_height);
+            let lines = ascii.lines().collect::<Vec<_>>();
+            let message = format!("{max_width}x{max_height}:\n{ascii}");
+            assert!(lines.len() <= max_height, "{}", message);
+            assert!(
+                lines.iter().all(|l| l.chars().count() <= max_width),
+                "{}",
+                message
+            );
+        }
+    }
+}
@@ -0,0 +1,296 @@
+//! Counts and generates passwords without checking every number in a range.
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::ops::RangeInclusive;
+
+use crate::error::Error;
+use crate::rules::{digits, Rule, Rules};
+
+/// The rules in a form the digit-by-digit count can track.
+struct Counter {
+    digit_counts: Vec<usize>,
+    non_decreasing: bool,
+    at_least: Vec<usize>,
+    exactly: Vec<usize>,
+    /// Runs longer than every rule's length are counted as this length.
+    max_run: usize,
+    memo: HashMap<(usize, u8, usize, u32), u128>,
+}
+
+impl Counter {
+    fn new(rules: &Rules) -> Result<Self, Error> {
+        let mut counter = Counter {
+            digit_counts: Vec::new(),
+            non_decreasing: false,
+            at_least: Vec::new(),
+            exactly: Vec::new(),
+            max_run: 1,
+            memo: HashMap::new(),
+        };
+        for rule in rules.rules() {
+            match rule {
+                Rule::DigitCount(n) => counter.digit_counts.push(*n),
+                Rule::NonDecreasing => counter.non_decreasing = true,
+                Rule::RunAtLeast(k) => counter.at_least.push(*k),
+                Rule::RunExactly(k) => counter.exactly.push(*k),
+                Rule::Custom(_) => return Err(Error::UnsupportedRule(format!("{rule:?}"))),
+            }
+        }
+        if counter.at_least.len() + counter.exactly.len() > 32 {
+            return Err(Error::UnsupportedRule(String::from(
+                "more than 32 run rules",
+            )));
+        }
+        counter.max_run = counter
+            .at_least
+            .iter()
+            .chain(counter.exactly.iter())
+            .max()
+            .map_or(1, |k| k + 1);
+        Ok(counter)
+    }
+
+    fn all_flags(&self) -> u32 {
+        let n = self.at_least.len() + self.exactly.len();
+        if n == 32 {
+            u32::MAX
+        } else {
+            (1 << n) - 1
+        }
+    }
+
+    /// Sets the flags of the rules satisfied by a run which has reached `run` digits.
+    fn flags_for_run(&self, run: usize) -> u32 {
+        self.at_least
+            .iter()
+            .enumerate()
+            .filter(|(_, &k)| run >= k)
+            .fold(0, |flags, (i, _)| flags | 1 << i)
+    }
+
+    /// Sets the flags of the rules satisfied by a run which ended after `run` digits.
+    fn flags_for_ended_run(&self, run: usize) -> u32 {
+        let offset = self.at_least.len();
+        self.exactly
+            .iter()
+            .enumerate()
+            .filter(|(_, &k)| run == k)
+            .fold(0, |flags, (i, _)| flags | 1 << (offset + i))
+    }
+
+    /// Counts the valid numbers with exactly `len` digits which are at most `bound`, or any
+    /// `len` digit numbers if `bound` is `None`.
+    fn count_len(&mut self, len: usize, bound: Option<&[u8]>) -> u128 {
+        if !self.digit_counts.iter().all(|&n| n == len) {
+            return 0;
+        }
+        let first = if len == 1 { 0 } else { 1 };
+        (first..=bound.map_or(9, |b| b[0]))
+            .map(|d| {
+                let tight = bound.filter(|b| b[0] == d);
+                self.count_rest(len - 1, d, 1, self.flags_for_run(1), tight.map(|b| &b[1..]))
+            })
+            .sum()
+    }
+
+    /// Counts the ways to choose the remaining digits after `prev`, which ended a run of `run`
+    /// digits, if the digits chosen so far equal the bound's and the rest must be at most
+    /// `bound`.
+    fn count_rest(
+        &mut self,
+        remaining: usize,
+        prev: u8,
+        run: usize,
+        flags: u32,
+        bound: Option<&[u8]>,
+    ) -> u128 {
+        if remaining == 0 {
+            let flags = flags | self.flags_for_ended_run(run);
+            return u128::from(flags == self.all_flags());
+        }
+
+        let key = (remaining, prev, run, flags);
+        if bound.is_none() {
+            if let Some(&count) = self.memo.get(&key) {
+                return count;
+            }
+        }
+
+        let lo = if self.non_decreasing { prev } else { 0 };
+        let hi = bound.map_or(9, |b| b[0]);
+        let mut count = 0;
+        for d in lo..=hi {
+            let (run, ended) = if d == prev {
+                ((run + 1).min(self.max_run), 0)
+            } else {
+                (1, self.flags_for_ended_run(run))
+            };
+            let flags = flags | ended | self.flags_for_run(run);
+            let tight = bound.filter(|b| b[0] == d).map(|b| &b[1..]);
+            count += self.count_rest(remaining - 1, d, run, flags, tight);
+        }
+
+        if bound.is_none() {
+            self.memo.insert(key, count);
+        }
+        count
+    }
+
+    /// Counts the valid numbers in `[0, n]`.
+    fn count_upto(&mut self, n: u64) -> u128 {
+        let bound = digits(n);
+        (1..bound.len())
+            .map(|len| self.count_len(len, None))
+            .sum::<u128>()
+            + self.count_len(bound.len(), Some(&bound))
+    }
+}
+
+/// Counts the passwords in the range which follow the rules, without checking each number.
+///
+/// Returns `Error::UnsupportedRule` if there is a custom rule.
+pub fn count_passwords(rng: RangeInclusive<u64>, rules: &Rules) -> Result<u128, Error> {
+    let mut counter = Counter::new(rules)?;
+    let (lo, hi) = rng.into_inner();
+    if lo > hi {
+        return Ok(0);
+    }
+    let below = if lo == 0 {
+        0
+    } else {
+        counter.count_upto(lo - 1)
+    };
+    Ok(counter.count_upto(hi) - below)
+}
+
+/// The numbers with non-decreasing digits in a range, in increasing order.
+#[derive(Clone, Debug)]
+pub struct NonDecreasing {
+    next: Option<u64>,
+    hi: u64,
+}
+
+/// The smallest number at least `n` with non-decreasing digits, if it fits in a `u64`.
+fn next_non_decreasing(n: u64) -> Option<u64> {
+    let mut digits = digits(n);
+    if let Some(i) = (1..digits.len()).find(|&i| digits[i] < digits[i - 1]) {
+        let d = digits[i - 1];
+        digits[i..].iter_mut().for_each(|x| *x = d);
+    }
+    let value = digits.iter().fold(0_u128, |v, &d| v * 10 + u128::from(d));
+    u64::try_from(value).ok()
+}
+
+/// Generates the numbers with non-decreasing digits in the range, skipping the numbers between
+/// them.
+#[must_use]
+pub fn non_decreasing(rng: RangeInclusive<u64>) -> NonDecreasing {
+    let (lo, hi) = rng.into_inner();
+    NonDecreasing {
+        next: next_non_decreasing(lo),
+        hi,
+    }
+}
+
+impl Iterator for NonDecreasing {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let n = self.next.filter(|&n| n <= self.hi)?;
+        self.next = n.checked_add(1).and_then(next_non_decreasing);
+        Some(n)
+    }
+}
+
+/// Generates the passwords in the range which follow the rules, in increasing order.
+///
+/// Only numbers with non-decreasing digits are checked if the rules require them.
+pub fn passwords<'a>(
+    rng: RangeInclusive<u64>,
+    rules: &'a Rules,
+) -> Box<dyn Iterator<Item = u64> + 'a> {
+    if rules
+        .rules()
+        .iter()
+        .any(|r| matches!(r, Rule::NonDecreasing))
+    {
+        Box::new(non_decreasing(rng).filter(move |&p| rules.is_valid(p)))
+    } else {
+        Box::new(rng.filter(move |&p| rules.is_valid(p)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule_sets() -> Vec<Rules> {
+        vec![
+            Rules::part1(),
+            Rules::part2(),
+            Rules::new(),
+            Rules::new().with(Rule::NonDecreasing),
+            Rules::new().with(Rule::RunAtLeast(3)),
+            Rules::new()
+                .with(Rule::RunExactly(1))
+                .with(Rule::RunExactly(2)),
+            Rules::new()
+                .with(Rule::DigitCount(4))
+                .with(Rule::NonDecreasing)
+                .with(Rule::RunAtLeast(2))
+                .with(Rule::RunExactly(3)),
+        ]
+    }
+
+    #[test]
+    fn counts_match_brute_force() {
+        for rules in rule_sets() {
+            for rng in [0..=0, 0..=20_000, 987..=4321, 111_000..=125_000] {
+                let expected = rng.clone().filter(|&p| rules.is_valid(p)).count() as u128;
+                assert_eq!(
+                    expected,
+                    count_passwords(rng.clone(), &rules).unwrap(),
+                    "{rules:?} {rng:?}"
+                );
+                assert_eq!(
+                    expected,
+                    passwords(rng.clone(), &rules).count() as u128,
+                    "{rules:?} {rng:?}"
+                );
+            }
+        }
+        #[allow(clippy::reversed_empty_ranges)]
+        let empty = 5..=4;
+        assert_eq!(0, count_passwords(empty, &Rules::part1()).unwrap());
+    }
+
+    #[test]
+    fn non_decreasing_jumps() {
+        assert_eq!(
+            vec![99, 111, 112],
+            non_decreasing(99..=112).collect::<Vec<_>>()
+        );
+        assert_eq!(Some(2_999), next_non_decreasing(2_910));
+        assert_eq!(0, non_decreasing(u64::MAX - 1..=u64::MAX).count());
+        assert_eq!(None, next_non_decreasing(18_446_744_073_709_551_615));
+    }
+
+    #[test]
+    fn wide_passwords() {
+        let rules = Rules::new()
+            .with(Rule::DigitCount(12))
+            .with(Rule::NonDecreasing)
+            .with(Rule::RunExactly(2));
+        let rng = 100_000_000_000..=999_999_999_999;
+        let count = count_passwords(rng.clone(), &rules).unwrap();
+        assert_eq!(passwords(rng, &rules).count() as u128, count);
+
+        let all = Rules::new().with(Rule::RunAtLeast(1));
+        assert_eq!(1 << 64, count_passwords(0..=u64::MAX, &all).unwrap());
+        assert!(matches!(
+            count_passwords(0..=10, &Rules::new().with(Rule::custom(|_| true))),
+            Err(Error::UnsupportedRule(_))
+        ));
+    }
+}
@@ -0,0 +1,32 @@
+use std::{
+    error,
+    fmt::{self, Debug, Display},
+    num,
+};
+
+#[derive(Debug)]
+pub enum Error {
+    ParseIntError(num::ParseIntError),
+    /// The range is not written as `lo-hi`.
+    InvalidRange(String),
+    /// The rule cannot be counted without checking every number.
+    UnsupportedRule(String),
+}
+
+impl error::Error for Error {}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::ParseIntError(e) => Display::fmt(e, f),
+            Error::InvalidRange(s) => write!(f, "invalid range: {s:?}"),
+            Error::UnsupportedRule(s) => write!(f, "unsupported rule: {s}"),
+        }
+    }
+}
+
+impl From<num::ParseIntError> for Error {
+    fn from(other: num::ParseIntError) -> Self {
+        Error::ParseIntError(other)
+    }
+}
@@ -1,43 +1,33 @@
 use std::ops::RangeInclusive;
 
+pub mod count;
+pub mod error;
+pub mod rules;
+
+use error::Error;
+use rules::Rules;
+
 #[must_use] pub fn find_possible_passwords(rng: RangeInclusive<u32>) -> Vec<u32> {
     rng.filter(|p| is_possible_password(*p)).collect()
 }
 
-fn is_possible_password(p: u32) -> bool {
-    if !(100_000..=999_999).contains(&p) {
-        return false;
-    }
-
-    let mut remainder = p;
-    let mut last_digit = 0;
-    let mut repeated_digit_exactly_twice = false;
-    let mut repeated_digit_counter: usize = 0;
-
-    for idx in 1..7 {
-        let m = 10u32.pow(6 - idx);
-        let cur_digit = remainder / m;
-        if last_digit > cur_digit {
-            return false;
-        }
-        if last_digit == cur_digit {
-            repeated_digit_counter += 1;
-        } else {
-            if repeated_digit_counter == 1 {
-                repeated_digit_exactly_twice = true;
-            }
-            repeated_digit_counter = 0;
-        }
-        last_digit = cur_digit;
-
-        remainder %= m;
-    }
+/// Every password in the range which follows the rules.
+#[must_use]
+pub fn find_passwords(rng: RangeInclusive<u64>, rules: &Rules) -> Vec<u64> {
+    rng.filter(|&p| rules.is_valid(p)).collect()
+}
 
-    if repeated_digit_counter == 1 {
-        repeated_digit_exactly_twice = true;
-    }
+/// Parses a range written as `lo-hi`.
+pub fn parse_range(input: &str) -> Result<RangeInclusive<u64>, Error> {
+    let (lo, hi) = input
+        .trim()
+        .split_once('-')
+        .ok_or_else(|| Error::InvalidRange(input.to_string()))?;
+    Ok(lo.trim().parse()?..=hi.trim().parse()?)
+}
 
-    repeated_digit_exactly_twice
+fn is_possible_password(p: u32) -> bool {
+    Rules::part2().is_valid(u64::from(p))
 }
 
 #[cfg(test)]
@@ -78,4 +68,15 @@ mod test {
     fn is_possible_ex7() {
         assert!(is_possible_password(111_122));
     }
+
+    #[test]
+    fn range() {
+        assert_eq!(264_360..=746_325, parse_range("264360-746325\n").unwrap());
+        assert!(matches!(parse_range("264360"), Err(Error::InvalidRange(_))));
+        assert!(matches!(parse_range("a-3"), Err(Error::ParseIntError(_))));
+        assert_eq!(
+            vec![111_111, 111_112],
+            find_passwords(111_110..=111_112, &Rules::part1())
+        );
+    }
 }
@@ -1,8 +1,23 @@
+use std::env;
 
+use aoc_2019_4::{count::count_passwords, error::Error, rules::Rules};
 
-fn main() {
-    let rng = 264_360..=746_325;
+/// Prints the number of passwords in the range following the part 1 and part 2 rules.
+///
+/// The range is given as `lo-hi` or as `lo hi`.
+fn main() -> Result<(), Error> {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    let rng = match args.as_slice() {
+        [range] => aoc_2019_4::parse_range(range)?,
+        [lo, hi] => aoc_2019_4::parse_range(&format!("{lo}-{hi}"))?,
+        _ => {
+            eprintln!("usage: aoc_2019_4 <lo>-<hi>");
+            std::process::exit(2);
+        }
+    };
 
-    let possible_passwords = aoc_2019_4::find_possible_passwords(rng);
-    println!("{}", possible_passwords.len());
+    println!("{}", count_passwords(rng.clone(), &Rules::part1())?);
+    println!("{}", count_passwords(rng, &Rules::part2())?);
+
+    Ok(())
 }
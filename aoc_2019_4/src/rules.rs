@@ -0,0 +1,160 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::rc::Rc;
+
+/// A predicate on a password's digits, most significant first.
+pub type Predicate = Rc<dyn Fn(&[u8]) -> bool>;
+
+/// A rule a password's digits must follow.
+#[derive(Clone)]
+pub enum Rule {
+    /// The password has exactly this many digits.
+    DigitCount(usize),
+    /// Each digit is at least the digit before it.
+    NonDecreasing,
+    /// Some digit is repeated at least this many times in a row.
+    RunAtLeast(usize),
+    /// Some digit is repeated exactly this many times in a row, not as part of a longer run.
+    RunExactly(usize),
+    /// The digits, most significant first, satisfy the predicate.
+    Custom(Predicate),
+}
+
+impl fmt::Debug for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Rule::DigitCount(n) => f.debug_tuple("DigitCount").field(n).finish(),
+            Rule::NonDecreasing => f.write_str("NonDecreasing"),
+            Rule::RunAtLeast(k) => f.debug_tuple("RunAtLeast").field(k).finish(),
+            Rule::RunExactly(k) => f.debug_tuple("RunExactly").field(k).finish(),
+            Rule::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+impl Rule {
+    /// Creates a rule from a predicate on the digits, most significant first.
+    pub fn custom<F: Fn(&[u8]) -> bool + 'static>(f: F) -> Self {
+        Rule::Custom(Rc::new(f))
+    }
+
+    #[must_use]
+    pub fn is_satisfied(&self, digits: &[u8]) -> bool {
+        match self {
+            Rule::DigitCount(n) => digits.len() == *n,
+            Rule::NonDecreasing => digits.windows(2).all(|w| w[0] <= w[1]),
+            Rule::RunAtLeast(k) => runs(digits).any(|len| len >= *k),
+            Rule::RunExactly(k) => runs(digits).any(|len| len == *k),
+            Rule::Custom(f) => f(digits),
+        }
+    }
+}
+
+/// The lengths of the runs of equal digits.
+fn runs(digits: &[u8]) -> impl Iterator<Item = usize> + '_ {
+    let mut rest = digits;
+    std::iter::from_fn(move || {
+        let first = *rest.first()?;
+        let len = rest.iter().take_while(|&&d| d == first).count();
+        rest = &rest[len..];
+        Some(len)
+    })
+}
+
+/// The decimal digits of the number, most significant first.
+#[must_use]
+pub fn digits(mut n: u64) -> Vec<u8> {
+    let mut digits = Vec::new();
+    loop {
+        digits.push(u8::try_from(n % 10).expect("digit to fit in u8"));
+        n /= 10;
+        if n == 0 {
+            break;
+        }
+    }
+    digits.reverse();
+    digits
+}
+
+/// Rules which a password must all follow.
+#[derive(Clone, Debug, Default)]
+pub struct Rules {
+    rules: Vec<Rule>,
+}
+
+impl Rules {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a rule.
+    #[must_use]
+    pub fn with(mut self, rule: Rule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+
+    /// Six non-decreasing digits with some digit repeated at least twice in a row.
+    #[must_use]
+    pub fn part1() -> Self {
+        Self::new()
+            .with(Rule::DigitCount(6))
+            .with(Rule::NonDecreasing)
+            .with(Rule::RunAtLeast(2))
+    }
+
+    /// Six non-decreasing digits with some digit repeated exactly twice in a row.
+    #[must_use]
+    pub fn part2() -> Self {
+        Self::new()
+            .with(Rule::DigitCount(6))
+            .with(Rule::NonDecreasing)
+            .with(Rule::RunExactly(2))
+    }
+
+    #[must_use]
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    #[must_use]
+    pub fn is_valid(&self, password: u64) -> bool {
+        let digits = digits(password);
+        self.rules.iter().all(|r| r.is_satisfied(&digits))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets() {
+        assert!(Rules::part1().is_valid(111_111));
+        assert!(!Rules::part1().is_valid(223_450));
+        assert!(!Rules::part1().is_valid(123_789));
+        assert!(Rules::part1().is_valid(123_444));
+        assert!(!Rules::part2().is_valid(123_444));
+        assert!(Rules::part2().is_valid(111_122));
+        assert!(!Rules::part1().is_valid(11_122));
+    }
+
+    #[test]
+    fn composed_rules() {
+        assert_eq!(vec![1, 2, 0, 3], digits(1203));
+        assert_eq!(vec![0], digits(0));
+        assert_eq!(vec![3, 1, 2], runs(&[4, 4, 4, 5, 6, 6]).collect::<Vec<_>>());
+
+        let rules = Rules::new()
+            .with(Rule::RunAtLeast(3))
+            .with(Rule::custom(|d| {
+                d.iter().map(|&d| u32::from(d)).sum::<u32>() % 2 == 0
+            }));
+        assert!(rules.is_valid(81_113));
+        assert!(!rules.is_valid(81_112));
+        assert!(!rules.is_valid(81_213));
+        assert!(Rules::new().is_valid(12));
+        assert_eq!(2, rules.rules().len());
+    }
+}
@@ -7,6 +7,22 @@ use std::{
 #[derive(Debug)]
 pub enum Error {
     IoErr(io::Error),
+    /// A line which is not `PARENT)OBJECT`. Lines are numbered from 1.
+    MalformedLine {
+        line: usize,
+        text: String,
+    },
+    /// An object given a second parent on the line.
+    DuplicateParent {
+        line: usize,
+        object: String,
+        first: String,
+        second: String,
+    },
+    /// Objects which orbit each other in a loop, starting from the smallest name.
+    Cycle(Vec<String>),
+    /// Objects which orbit nothing, sorted by name. A map has exactly one root.
+    MultipleRoots(Vec<String>),
 }
 
 impl error::Error for Error {}
@@ -15,6 +31,27 @@ impl Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             Error::IoErr(e) => Display::fmt(e, f),
+            Error::MalformedLine { line, text } => {
+                write!(f, "line {line}: expected PARENT)OBJECT, found {text:?}")
+            }
+            Error::DuplicateParent {
+                line,
+                object,
+                first,
+                second,
+            } => write!(
+                f,
+                "line {line}: {object} orbits {second} but already orbits {first}"
+            ),
+            Error::Cycle(objects) => {
+                let names = objects
+                    .iter()
+                    .chain(objects.first())
+                    .map(String::as_str)
+                    .collect::<Vec<_>>();
+                write!(f, "orbit cycle: {}", names.join(" -> "))
+            }
+            Error::MultipleRoots(roots) => write!(f, "multiple roots: {}", roots.join(", ")),
         }
     }
 }
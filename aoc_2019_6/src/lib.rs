@@ -1,46 +1,149 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::str::FromStr;
+
+use crate::error::Error;
 
 pub mod error;
 
-pub struct OrbitMap {
-    map: HashMap<String, String>,
+/// Collects orbits and checks that they form a single tree before building an `OrbitMap`.
+#[derive(Clone, Debug, Default)]
+pub struct OrbitMapBuilder {
+    parents: HashMap<String, String>,
+    lines: usize,
 }
 
-impl Default for OrbitMap {
-    fn default() -> Self {
-        Self::new()
+impl OrbitMapBuilder {
+    #[must_use] pub fn new() -> Self {
+        Self::default()
     }
-}
 
-impl OrbitMap {
-    #[must_use] pub fn new() -> Self {
-        OrbitMap {
-            map: HashMap::new(),
+    /// Adds the orbit on the next line of input.
+    ///
+    /// Blank lines are skipped but still counted, so errors report the line in the input.
+    pub fn add_line(&mut self, text: &str) -> Result<(), Error> {
+        self.lines += 1;
+        if text.trim().is_empty() {
+            return Ok(());
         }
+        let (parent, object) = parse_orbit(text).ok_or_else(|| Error::MalformedLine {
+            line: self.lines,
+            text: text.trim_end().to_string(),
+        })?;
+        self.add(object, parent)
     }
 
-    pub fn insert(&mut self, object: &str, object_orbits: &str) {
-        self.map
-            .insert(object.to_string(), object_orbits.to_string());
+    /// Adds that `object` orbits `parent`, counting as the next line of input.
+    pub fn insert(&mut self, object: &str, parent: &str) -> Result<(), Error> {
+        self.lines += 1;
+        self.add(object, parent)
     }
 
-    fn orbits_for(&self, object: &str) -> Option<usize> {
-        let mut orbits = 0;
-        let mut cur_obj = object;
-        while let Some(object_orbits) = self.map.get(cur_obj) {
-            orbits += 1;
-            cur_obj = object_orbits;
+    fn add(&mut self, object: &str, parent: &str) -> Result<(), Error> {
+        if let Some(first) = self.parents.get(object) {
+            return Err(Error::DuplicateParent {
+                line: self.lines,
+                object: object.to_string(),
+                first: first.clone(),
+                second: parent.to_string(),
+            });
         }
+        self.parents.insert(object.to_string(), parent.to_string());
+        Ok(())
+    }
 
-        if orbits == 0 {
-            None
-        } else {
-            Some(orbits)
+    /// Checks that the orbits have no cycles and a single root.
+    pub fn build(self) -> Result<OrbitMap, Error> {
+        let mut depths: HashMap<String, usize> = HashMap::with_capacity(self.parents.len());
+
+        // Walk from each object toward the root until reaching an object with a known depth.
+        // Objects are visited in order so the same cycle is always reported.
+        let mut objects = self.parents.keys().collect::<Vec<_>>();
+        objects.sort();
+        for start in objects {
+            let mut path: Vec<&str> = Vec::new();
+            let mut on_path: HashMap<&str, usize> = HashMap::new();
+            let mut cur = start.as_str();
+            let base = loop {
+                if let Some(&depth) = depths.get(cur) {
+                    break depth;
+                }
+                if let Some(&i) = on_path.get(cur) {
+                    let mut cycle = path[i..].iter().map(|o| o.to_string()).collect::<Vec<_>>();
+                    let min = (0..cycle.len()).min_by_key(|&j| &cycle[j]).unwrap_or(0);
+                    cycle.rotate_left(min);
+                    return Err(Error::Cycle(cycle));
+                }
+                match self.parents.get(cur) {
+                    Some(parent) => {
+                        on_path.insert(cur, path.len());
+                        path.push(cur);
+                        cur = parent;
+                    }
+                    None => break 0,
+                }
+            };
+            for (i, object) in path.iter().rev().enumerate() {
+                depths.insert(object.to_string(), base + i + 1);
+            }
+        }
+
+        let roots = self
+            .parents
+            .values()
+            .filter(|p| !self.parents.contains_key(*p))
+            .cloned()
+            .collect::<BTreeSet<_>>();
+        if roots.len() > 1 {
+            return Err(Error::MultipleRoots(roots.into_iter().collect()));
         }
+
+        Ok(OrbitMap {
+            parents: self.parents,
+            depths,
+        })
+    }
+}
+
+/// Orbits which form a single tree, so every query terminates.
+#[derive(Clone, Debug, Default)]
+pub struct OrbitMap {
+    parents: HashMap<String, String>,
+    /// The number of direct and indirect orbits of each object which orbits something.
+    depths: HashMap<String, usize>,
+}
+
+impl FromStr for OrbitMap {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut builder = OrbitMapBuilder::new();
+        for line in s.lines() {
+            builder.add_line(line)?;
+        }
+        builder.build()
+    }
+}
+
+impl OrbitMap {
+    #[must_use] pub fn builder() -> OrbitMapBuilder {
+        OrbitMapBuilder::new()
+    }
+
+    /// The object which every other object orbits, or `None` if the map is empty.
+    #[must_use] pub fn root(&self) -> Option<&str> {
+        self.parents
+            .values()
+            .find(|p| !self.parents.contains_key(*p))
+            .map(String::as_str)
+    }
+
+    /// The number of objects which `object` directly and indirectly orbits.
+    #[must_use] pub fn orbits_for(&self, object: &str) -> Option<usize> {
+        self.depths.get(object).copied()
     }
 
     #[must_use] pub fn total_orbits(&self) -> Option<usize> {
-        let total_orbits: usize = self.map.keys().filter_map(|k| self.orbits_for(k)).sum();
+        let total_orbits: usize = self.depths.values().sum();
         if total_orbits == 0 {
             None
         } else {
@@ -52,7 +155,7 @@ impl OrbitMap {
         let mut objects_orbited: Vec<&str> = Vec::new();
 
         let mut cur_obj = object;
-        while let Some(object_orbits) = self.map.get(cur_obj) {
+        while let Some(object_orbits) = self.parents.get(cur_obj) {
             objects_orbited.push(object_orbits);
             cur_obj = object_orbits;
         }
@@ -85,11 +188,13 @@ impl OrbitMap {
     }
 }
 
-/// Parse an orbit of a planet.
-#[must_use] pub fn parse_orbit(input: &str) -> (&str, &str) {
-    let input = input.trim().split(')').collect::<Vec<&str>>();
-    assert_eq!(input.len(), 2);
-    (input[0], input[1])
+/// Parse an orbit of a planet, or `None` if the line is not `PARENT)OBJECT`.
+#[must_use] pub fn parse_orbit(input: &str) -> Option<(&str, &str)> {
+    let (parent, object) = input.trim().split_once(')')?;
+    if parent.is_empty() || object.is_empty() || object.contains(')') {
+        return None;
+    }
+    Some((parent, object))
 }
 
 #[cfg(test)]
@@ -98,15 +203,18 @@ mod tests {
 
     #[test]
     fn test_parse_orbit() {
-        let (object_orbits, object) = parse_orbit("COM)B");
+        let (object_orbits, object) = parse_orbit("COM)B").unwrap();
         assert_eq!(object_orbits, "COM");
         assert_eq!(object, "B");
+        assert_eq!(parse_orbit(" A)B \n"), Some(("A", "B")));
+        assert_eq!(parse_orbit("COMB"), None);
+        assert_eq!(parse_orbit(")B"), None);
+        assert_eq!(parse_orbit("A)"), None);
+        assert_eq!(parse_orbit("A)B)C"), None);
     }
 
     #[test]
     fn ex1() {
-        let mut orbit_map = OrbitMap::new();
-
         let input = "COM)B
 B)C
 C)D
@@ -120,22 +228,17 @@ J)K
 K)L
         ";
 
-        input
-            .trim()
-            .split('\n')
-            .map(parse_orbit)
-            .for_each(|(object_orbits, object)| orbit_map.insert(object, object_orbits));
+        let orbit_map = input.parse::<OrbitMap>().unwrap();
 
         assert_eq!(orbit_map.orbits_for("D"), Some(3));
         assert_eq!(orbit_map.orbits_for("L"), Some(7));
         assert_eq!(orbit_map.orbits_for("COM"), None);
+        assert_eq!(orbit_map.root(), Some("COM"));
         assert_eq!(orbit_map.total_orbits(), Some(42));
     }
 
     #[test]
     fn ex2() {
-        let mut orbit_map = OrbitMap::new();
-
         let input = "COM)B
 B)C
 C)D
@@ -150,12 +253,74 @@ K)L
 K)YOU
 I)SAN";
 
-        input
-            .trim()
-            .split('\n')
-            .map(parse_orbit)
-            .for_each(|(object_orbits, object)| orbit_map.insert(object, object_orbits));
+        let orbit_map = input.parse::<OrbitMap>().unwrap();
 
         assert_eq!(orbit_map.orbital_transfers_between("YOU", "SAN"), Some(4));
     }
+
+    #[test]
+    fn builder() {
+        let mut builder = OrbitMap::builder();
+        builder.insert("B", "COM").unwrap();
+        builder.insert("C", "B").unwrap();
+        builder.add_line("B)D").unwrap();
+        let orbit_map = builder.build().unwrap();
+        assert_eq!(orbit_map.total_orbits(), Some(5));
+        assert_eq!(orbit_map.orbital_transfers_between("C", "D"), Some(0));
+
+        let empty = OrbitMap::builder().build().unwrap();
+        assert_eq!(empty.root(), None);
+        assert_eq!(empty.total_orbits(), None);
+    }
+
+    #[test]
+    fn malformed_lines() {
+        match "COM)B\n\nB-C\n".parse::<OrbitMap>() {
+            Err(Error::MalformedLine { line, text }) => {
+                assert_eq!(line, 3);
+                assert_eq!(text, "B-C");
+            }
+            r => panic!("unexpected result: {:?}", r),
+        }
+    }
+
+    #[test]
+    fn duplicate_parents() {
+        match "COM)B\nB)C\nCOM)C".parse::<OrbitMap>() {
+            Err(Error::DuplicateParent {
+                line,
+                object,
+                first,
+                second,
+            }) => {
+                assert_eq!(line, 3);
+                assert_eq!(object, "C");
+                assert_eq!(first, "B");
+                assert_eq!(second, "COM");
+            }
+            r => panic!("unexpected result: {:?}", r),
+        }
+    }
+
+    #[test]
+    fn cycles() {
+        match "COM)B\nD)C\nE)D\nC)E".parse::<OrbitMap>() {
+            Err(e @ Error::Cycle(_)) => {
+                assert_eq!(e.to_string(), "orbit cycle: C -> D -> E -> C");
+            }
+            r => panic!("unexpected result: {:?}", r),
+        }
+        match "A)A".parse::<OrbitMap>() {
+            Err(Error::Cycle(objects)) => assert_eq!(objects, vec!["A"]),
+            r => panic!("unexpected result: {:?}", r),
+        }
+    }
+
+    #[test]
+    fn multiple_roots() {
+        match "COM)B\nX)Y\nB)C\nZ)Y2".parse::<OrbitMap>() {
+            Err(Error::MultipleRoots(roots)) => assert_eq!(roots, vec!["COM", "X", "Z"]),
+            r => panic!("unexpected result: {:?}", r),
+        }
+    }
 }
@@ -1,20 +1,18 @@
-use std::io::{self};
+use std::io::{self, Read};
+use std::process;
 
-use aoc_2019_6::{self, error::Error, OrbitMap};
+use aoc_2019_6::{error::Error, OrbitMap};
 
 fn main() -> Result<(), Error> {
-    let mut orbit_map = OrbitMap::new();
-
-    loop {
-        let mut input = String::new();
-        let bytes_read = io::stdin().read_line(&mut input)?;
-        if bytes_read == 0 {
-            break;
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+    let orbit_map = match input.parse::<OrbitMap>() {
+        Ok(orbit_map) => orbit_map,
+        Err(e) => {
+            eprintln!("{e}");
+            process::exit(1);
         }
-
-        let (object_orbits, object) = aoc_2019_6::parse_orbit(&input);
-        orbit_map.insert(object, object_orbits);
-    }
+    };
 
     println!("Total Orbits: {:?}", orbit_map.total_orbits());
 
@@ -9,6 +9,7 @@ pub enum Error {
     IoErr(io::Error),
     TryFromIntError(num::TryFromIntError),
     ParseIntError(num::ParseIntError),
+    Ocr(aoc_ocr::error::Error),
 }
 
 impl error::Error for Error {
@@ -17,6 +18,7 @@ impl error::Error for Error {
             Error::IoErr(e) => Some(e),
             Error::TryFromIntError(e) => Some(e),
             Error::ParseIntError(e) => Some(e),
+            Error::Ocr(e) => Some(e),
         }
     }
 }
@@ -27,6 +29,7 @@ impl Display for Error {
             Error::IoErr(e) => Display::fmt(e, f),
             Error::TryFromIntError(e) => Display::fmt(e, f),
             Error::ParseIntError(e) => Display::fmt(e, f),
+            Error::Ocr(e) => Display::fmt(e, f),
         }
     }
 }
@@ -48,3 +51,9 @@ impl From<num::ParseIntError> for Error {
         Error::ParseIntError(other)
     }
 }
+
+impl From<aoc_ocr::error::Error> for Error {
+    fn from(other: aoc_ocr::error::Error) -> Self {
+        Error::Ocr(other)
+    }
+}
@@ -118,6 +118,12 @@ pub fn display(pixels: Vec<u8>, width: usize, height: usize) {
     }
 }
 
+/// Reads the letters in a composed image.
+pub fn read_text(pixels: &[u8], width: usize) -> Result<String, Error> {
+    let lit = pixels.iter().map(|&p| p == 1).collect::<Vec<_>>();
+    Ok(aoc_ocr::recognize(&lit, width)?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -146,4 +152,18 @@ mod tests {
         assert_eq!(composed_img[3], 0);
         assert_eq!(composed_img.len(), 4);
     }
+
+    #[test]
+    fn day8_read_text() {
+        #[rustfmt::skip]
+        let pixels = vec![
+            1, 1, 1, 1, 0, 1, 0, 0, 0,
+            1, 0, 0, 0, 0, 1, 0, 0, 0,
+            1, 1, 1, 0, 0, 1, 0, 0, 0,
+            1, 0, 0, 0, 0, 1, 0, 0, 0,
+            1, 0, 0, 0, 0, 1, 0, 0, 0,
+            1, 0, 0, 0, 0, 1, 1, 1, 1,
+        ];
+        assert_eq!("FL", read_text(&pixels, 9).unwrap());
+    }
 }
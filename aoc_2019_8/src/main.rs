@@ -10,7 +10,9 @@ fn main() -> Result<(), Error> {
     let img = SpaceImg::new(input, 25, 6);
     println!("{}", img.verify()?);
 
-    aoc_2019_8::display(img.compose()?, 25, 6);
+    let composed = img.compose()?;
+    println!("{}", aoc_2019_8::read_text(&composed, 25)?);
+    aoc_2019_8::display(composed, 25, 6);
 
     Ok(())
 }
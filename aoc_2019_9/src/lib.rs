@@ -138,6 +138,14 @@ impl Prog {
             state: ProgState::NotStarted,
         }
     }
+
+    #[must_use] pub fn mem_state(&self) -> &[i64] {
+        &self.mem_state
+    }
+
+    #[must_use] pub fn state(&self) -> ProgState {
+        self.state
+    }
 }
 
 impl Prog {
@@ -0,0 +1,191 @@
+use std::convert::TryFrom;
+use std::ops::RangeInclusive;
+
+use crate::program::{Instr, Param, Program, Subset};
+
+/// A small seeded pseudo-random number generator (splitmix64).
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A value in the range `[0, n)`.
+    pub fn below(&mut self, n: usize) -> usize {
+        assert!(n > 0);
+        usize::try_from(self.next_u64() % u64::try_from(n).unwrap()).unwrap()
+    }
+
+    pub fn range(&mut self, rng: RangeInclusive<i64>) -> i64 {
+        let len = usize::try_from(rng.end() - rng.start() + 1).unwrap();
+        rng.start() + i64::try_from(self.below(len)).unwrap()
+    }
+}
+
+/// Generates random valid programs within an opcode subset.
+#[derive(Clone, Debug)]
+pub struct Generator {
+    rng: Rng,
+    max_instrs: usize,
+    max_data: usize,
+}
+
+impl Generator {
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Generator {
+            rng: Rng::new(seed),
+            max_instrs: 12,
+            max_data: 8,
+        }
+    }
+
+    /// Generates a program which the reference model accepts as valid.
+    pub fn program(&mut self, subset: Subset) -> Program {
+        loop {
+            let program = self.candidate(subset);
+            if program.evaluate().is_some() {
+                return program;
+            }
+        }
+    }
+
+    fn value(&mut self, subset: Subset) -> i64 {
+        match subset {
+            Subset::Day2 => self.rng.range(0..=20),
+            Subset::Day5 | Subset::Day9 => self.rng.range(-20..=20),
+        }
+    }
+
+    fn load_param(&mut self, subset: Subset, data_len: usize) -> Param {
+        let modes = match subset {
+            Subset::Day2 => 1,
+            Subset::Day5 => 2,
+            Subset::Day9 => 3,
+        };
+        match self.rng.below(modes) {
+            0 => Param::Data(self.rng.below(data_len)),
+            1 => Param::Immediate(self.value(subset)),
+            _ => Param::Relative(i64::try_from(self.rng.below(data_len)).unwrap()),
+        }
+    }
+
+    fn store_param(&mut self, subset: Subset, data_len: usize) -> Param {
+        if subset == Subset::Day9 && self.rng.below(2) == 0 {
+            Param::Relative(i64::try_from(self.rng.below(data_len)).unwrap())
+        } else {
+            Param::Data(self.rng.below(data_len))
+        }
+    }
+
+    fn candidate(&mut self, subset: Subset) -> Program {
+        let instrs_len = 1 + self.rng.below(self.max_instrs);
+        let data_len = 1 + self.rng.below(self.max_data);
+        let ops = match subset {
+            Subset::Day2 => 2,
+            Subset::Day5 => 8,
+            Subset::Day9 => 9,
+        };
+
+        let mut instrs = Vec::with_capacity(instrs_len);
+        let mut input = Vec::new();
+        for pc in 0..instrs_len {
+            let instr = match self.rng.below(ops) {
+                0 => Instr::Add(
+                    self.load_param(subset, data_len),
+                    self.load_param(subset, data_len),
+                    self.store_param(subset, data_len),
+                ),
+                1 => Instr::Mul(
+                    self.load_param(subset, data_len),
+                    self.load_param(subset, data_len),
+                    self.store_param(subset, data_len),
+                ),
+                2 => {
+                    input.push(self.value(subset));
+                    Instr::Input(self.store_param(subset, data_len))
+                }
+                3 => Instr::Output(self.load_param(subset, data_len)),
+                4 => Instr::JumpIfTrue(
+                    self.load_param(subset, data_len),
+                    pc + 1 + self.rng.below(instrs_len - pc),
+                ),
+                5 => Instr::JumpIfFalse(
+                    self.load_param(subset, data_len),
+                    pc + 1 + self.rng.below(instrs_len - pc),
+                ),
+                6 => Instr::LessThan(
+                    self.load_param(subset, data_len),
+                    self.load_param(subset, data_len),
+                    self.store_param(subset, data_len),
+                ),
+                7 => Instr::Equals(
+                    self.load_param(subset, data_len),
+                    self.load_param(subset, data_len),
+                    self.store_param(subset, data_len),
+                ),
+                _ => Instr::AdjustRelativeBase(Param::Immediate(self.rng.range(-2..=2))),
+            };
+            instrs.push(instr);
+        }
+
+        let data = (0..data_len).map(|_| self.value(subset)).collect();
+        let mut program = Program {
+            instrs,
+            data,
+            input,
+        };
+
+        // Move the relative base into the data region with the first adjustment so relative
+        // parameters mostly stay in bounds.
+        let data_start = i64::try_from(program.data_start()).unwrap();
+        if let Some(Instr::AdjustRelativeBase(Param::Immediate(v))) = program
+            .instrs
+            .iter_mut()
+            .find(|i| matches!(i, Instr::AdjustRelativeBase(_)))
+        {
+            *v += data_start;
+        }
+
+        program
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generates_within_subset() {
+        let mut gen = Generator::new(7);
+        for subset in [Subset::Day2, Subset::Day5, Subset::Day9] {
+            for _ in 0..100 {
+                let program = gen.program(subset);
+                assert!(program.subset() <= subset);
+                assert!(program.evaluate().is_some());
+            }
+        }
+    }
+
+    #[test]
+    fn day2_values_are_not_negative() {
+        let mut gen = Generator::new(11);
+        for _ in 0..100 {
+            let program = gen.program(Subset::Day2);
+            assert!(program.mem_state().iter().all(|&v| v >= 0));
+        }
+    }
+}
@@ -0,0 +1,251 @@
+use std::collections::VecDeque;
+use std::convert::TryFrom;
+use std::panic::{self, AssertUnwindSafe};
+
+use crate::program::{Outcome, Program, Subset};
+
+/// An independently written Intcode interpreter in the workspace.
+#[derive(Copy, Clone, Debug)]
+pub struct Interpreter {
+    pub name: &'static str,
+    /// The largest opcode subset the interpreter supports.
+    pub subset: Subset,
+    /// Runs an initial memory state with the given input.
+    pub exec: fn(&[i64], &[i64]) -> Result<Outcome, String>,
+}
+
+impl Interpreter {
+    /// Runs a program's initial memory state with the given input.
+    ///
+    /// Errors and panics from the interpreter are reported as a message.
+    pub fn run(&self, mem_state: &[i64], input: &[i64]) -> Result<Outcome, String> {
+        let exec = self.exec;
+        panic::catch_unwind(AssertUnwindSafe(|| exec(mem_state, input)))
+            .unwrap_or_else(|_| Err(String::from("panicked")))
+    }
+}
+
+pub const INTERPRETERS: &[Interpreter] = &[
+    Interpreter {
+        name: "aoc_2019_2::run_prog",
+        subset: Subset::Day2,
+        exec: run_2019_2,
+    },
+    Interpreter {
+        name: "aoc_2019_5::run_prog",
+        subset: Subset::Day5,
+        exec: run_2019_5,
+    },
+    Interpreter {
+        name: "aoc_2019_7::run_prog",
+        subset: Subset::Day5,
+        exec: run_2019_7,
+    },
+    Interpreter {
+        name: "aoc_2019_9::Prog::run",
+        subset: Subset::Day9,
+        exec: run_2019_9,
+    },
+    Interpreter {
+        name: "aoc_2019_11::Prog::run",
+        subset: Subset::Day9,
+        exec: run_2019_11,
+    },
+    Interpreter {
+        name: "aoc_2019_13::intcode::Prog::run",
+        subset: Subset::Day9,
+        exec: run_2019_13,
+    },
+];
+
+/// A difference between an interpreter and the reference model.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Mismatch {
+    pub interpreter: &'static str,
+    pub expected: Outcome,
+    pub actual: Result<Outcome, String>,
+}
+
+/// Runs a valid program on every interpreter which supports it and compares the results
+/// against the reference model.
+///
+/// # Panics
+///
+/// Panics if the program is not valid.
+pub fn check(program: &Program, interpreters: &[Interpreter]) -> Result<(), Mismatch> {
+    let expected = program.evaluate().expect("program to be valid");
+    let subset = program.subset();
+    let mem_state = program.mem_state();
+
+    for interpreter in interpreters.iter().filter(|i| subset <= i.subset) {
+        let actual = interpreter.run(&mem_state, &program.input);
+        if actual.as_ref() != Ok(&expected) {
+            return Err(Mismatch {
+                interpreter: interpreter.name,
+                expected,
+                actual,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn parse_output<I>(output: I) -> Result<Vec<i64>, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    output
+        .into_iter()
+        .map(|o| o.trim().parse::<i64>().map_err(|e| e.to_string()))
+        .collect()
+}
+
+fn to_input(input: &[i64]) -> VecDeque<String> {
+    input.iter().map(ToString::to_string).collect()
+}
+
+fn run_2019_2(mem_state: &[i64], _input: &[i64]) -> Result<Outcome, String> {
+    let mem_state = mem_state
+        .iter()
+        .map(|&v| usize::try_from(v))
+        .collect::<Result<Vec<usize>, _>>()
+        .map_err(|e| e.to_string())?;
+    let mem_state = aoc_2019_2::run_prog(&mem_state)
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(i64::try_from)
+        .collect::<Result<Vec<i64>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(Outcome {
+        mem_state,
+        output: vec![],
+    })
+}
+
+struct Io {
+    input: VecDeque<String>,
+    output: Vec<String>,
+}
+
+impl Io {
+    fn new(input: &[i64]) -> Self {
+        Io {
+            input: to_input(input),
+            output: Vec::new(),
+        }
+    }
+}
+
+macro_rules! impl_prog_io {
+    ($krate:ident) => {
+        impl $krate::ProgInput for Io {
+            fn read(&mut self) -> Result<String, $krate::error::Error> {
+                self.input.pop_front().ok_or_else(|| {
+                    $krate::error::Error::IoErr(std::io::ErrorKind::UnexpectedEof.into())
+                })
+            }
+        }
+
+        impl $krate::ProgOutput for Io {
+            fn write(&mut self, output: &str) -> Result<(), $krate::error::Error> {
+                self.output.push(output.to_string());
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_prog_io!(aoc_2019_5);
+impl_prog_io!(aoc_2019_7);
+impl_prog_io!(aoc_2019_9);
+impl_prog_io!(aoc_2019_11);
+
+fn run_2019_5(mem_state: &[i64], input: &[i64]) -> Result<Outcome, String> {
+    let mut output = Io::new(&[]);
+    let mem_state =
+        aoc_2019_5::run_prog(mem_state, Io::new(input), &mut output).map_err(|e| e.to_string())?;
+    Ok(Outcome {
+        mem_state,
+        output: parse_output(output.output)?,
+    })
+}
+
+fn run_2019_7(mem_state: &[i64], input: &[i64]) -> Result<Outcome, String> {
+    let mut mem_state = mem_state.to_vec();
+    let mut io = Io::new(input);
+    let mut output = Io::new(&[]);
+    let state =
+        aoc_2019_7::run_prog(&mut mem_state, 0, &mut io, &mut output).map_err(|e| e.to_string())?;
+    if state != aoc_2019_7::ProgState::Halt {
+        return Err(format!("{state:?}"));
+    }
+    Ok(Outcome {
+        mem_state,
+        output: parse_output(output.output)?,
+    })
+}
+
+fn run_2019_9(mem_state: &[i64], input: &[i64]) -> Result<Outcome, String> {
+    let mut prog = aoc_2019_9::Prog::new(mem_state);
+    let mut io = Io::new(input);
+    let mut output = Io::new(&[]);
+    prog.run(&mut io, &mut output).map_err(|e| e.to_string())?;
+    if prog.state() != aoc_2019_9::ProgState::Halt {
+        return Err(format!("{:?}", prog.state()));
+    }
+    Ok(Outcome {
+        mem_state: prog.mem_state().to_vec(),
+        output: parse_output(output.output)?,
+    })
+}
+
+fn run_2019_11(mem_state: &[i64], input: &[i64]) -> Result<Outcome, String> {
+    let mut prog = aoc_2019_11::Prog::new(mem_state);
+    let mut io = Io::new(input);
+    let mut output = Io::new(&[]);
+    prog.run(&mut io, &mut output).map_err(|e| e.to_string())?;
+    if prog.state() != aoc_2019_11::ProgState::Halt {
+        return Err(format!("{:?}", prog.state()));
+    }
+    Ok(Outcome {
+        mem_state: prog.mem_state().to_vec(),
+        output: parse_output(output.output)?,
+    })
+}
+
+fn run_2019_13(mem_state: &[i64], input: &[i64]) -> Result<Outcome, String> {
+    let mut prog = aoc_2019_13::intcode::Prog::new(mem_state);
+    let mut input = to_input(input);
+    let mut output = VecDeque::new();
+    prog.run(&mut input, &mut output)
+        .map_err(|e| e.to_string())?;
+    if prog.state() != aoc_2019_13::intcode::ProgState::Halt {
+        return Err(format!("{:?}", prog.state()));
+    }
+    Ok(Outcome {
+        mem_state: prog.mem_state().to_vec(),
+        output: parse_output(output)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn waiting_for_input_is_not_halting() {
+        for interpreter in INTERPRETERS.iter().filter(|i| i.subset != Subset::Day2) {
+            assert!(
+                interpreter.run(&[3, 0, 99], &[]).is_err(),
+                "{}",
+                interpreter.name
+            );
+            assert!(
+                interpreter.run(&[3, 0, 99], &[7]).is_ok(),
+                "{}",
+                interpreter.name
+            );
+        }
+    }
+}
@@ -0,0 +1,61 @@
+//! Differential testing across the workspace's Intcode interpreters.
+//!
+//! Random valid programs are generated within each interpreter's supported opcode subset,
+//! run on every interpreter which supports them, and compared against a reference model.
+//! Failing programs are shrunk to a small reproducer.
+
+pub mod gen;
+pub mod interp;
+pub mod minimize;
+pub mod program;
+
+use gen::Generator;
+use interp::{Interpreter, Mismatch};
+use program::{Program, Subset};
+
+/// A minimized program which an interpreter runs differently from the reference model.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Failure {
+    pub program: Program,
+    pub mismatch: Mismatch,
+}
+
+/// Generates programs for every subset and checks each against the interpreters.
+///
+/// The failure is boxed since it carries the whole program and both memory states.
+pub fn fuzz(
+    seed: u64,
+    iterations: usize,
+    interpreters: &[Interpreter],
+) -> Result<(), Box<Failure>> {
+    let mut gen = Generator::new(seed);
+    for _ in 0..iterations {
+        for &subset in &[Subset::Day2, Subset::Day5, Subset::Day9] {
+            let program = gen.program(subset);
+            if let Err(mismatch) = interp::check(&program, interpreters) {
+                let program = minimize::minimize(&program, |p| {
+                    interp::check(p, interpreters)
+                        .map_err(|m| m.interpreter == mismatch.interpreter)
+                        .err()
+                        .unwrap_or(false)
+                });
+                let mismatch = interp::check(&program, interpreters).unwrap_err();
+                return Err(Box::new(Failure { program, mismatch }));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interp::INTERPRETERS;
+
+    #[test]
+    fn interpreters_agree() {
+        for seed in 0..4 {
+            assert_eq!(Ok(()), fuzz(seed, 250, INTERPRETERS));
+        }
+    }
+}
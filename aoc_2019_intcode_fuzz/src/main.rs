@@ -0,0 +1,41 @@
+use std::env;
+use std::process;
+
+use aoc_2019_intcode_fuzz::{self, interp::INTERPRETERS};
+
+fn main() {
+    let mut args = env::args().skip(1);
+    let iterations = args
+        .next()
+        .map_or(Ok(10_000), |a| a.parse::<usize>())
+        .unwrap_or_else(|e| {
+            eprintln!("invalid iterations: {e}");
+            process::exit(2);
+        });
+    let seed = args
+        .next()
+        .map_or(Ok(0), |a| a.parse::<u64>())
+        .unwrap_or_else(|e| {
+            eprintln!("invalid seed: {e}");
+            process::exit(2);
+        });
+
+    match aoc_2019_intcode_fuzz::fuzz(seed, iterations, INTERPRETERS) {
+        Ok(()) => println!("{iterations} iterations passed"),
+        Err(failure) => {
+            let mem_state = failure
+                .program
+                .mem_state()
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join(",");
+            println!("interpreter: {}", failure.mismatch.interpreter);
+            println!("program: {mem_state}");
+            println!("input: {:?}", failure.program.input);
+            println!("expected: {:?}", failure.mismatch.expected);
+            println!("actual: {:?}", failure.mismatch.actual);
+            process::exit(1);
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use crate::program::{Instr, Param, Program};
+
+/// Shrinks a program while it stays valid and still fails.
+///
+/// Repeatedly tries removing instructions, data cells and input, and simplifying values,
+/// keeping the first smaller candidate which still fails until none do.
+pub fn minimize<F>(program: &Program, mut is_failing: F) -> Program
+where
+    F: FnMut(&Program) -> bool,
+{
+    let mut best = program.clone();
+    while let Some(smaller) = candidates(&best)
+        .into_iter()
+        .find(|c| c.evaluate().is_some() && is_failing(c))
+    {
+        best = smaller;
+    }
+    best
+}
+
+fn without_instr(program: &Program, idx: usize) -> Program {
+    let mut instrs = program.instrs.clone();
+    instrs.remove(idx);
+    for instr in &mut instrs {
+        match instr {
+            Instr::JumpIfTrue(_, t) | Instr::JumpIfFalse(_, t) if *t > idx => *t -= 1,
+            _ => {}
+        }
+    }
+    Program {
+        instrs,
+        data: program.data.clone(),
+        input: program.input.clone(),
+    }
+}
+
+fn simpler_values(v: i64) -> Vec<i64> {
+    let mut values = Vec::new();
+    if v != 0 {
+        values.push(0);
+    }
+    if v / 2 != 0 && v / 2 != v {
+        values.push(v / 2);
+    }
+    values
+}
+
+fn simpler_params(p: Param) -> Vec<Param> {
+    match p {
+        Param::Data(_) => vec![Param::Immediate(0)],
+        Param::Immediate(v) => simpler_values(v)
+            .into_iter()
+            .map(Param::Immediate)
+            .collect(),
+        Param::Relative(v) => {
+            let mut params = vec![Param::Immediate(0)];
+            params.extend(simpler_values(v).into_iter().map(Param::Relative));
+            params
+        }
+    }
+}
+
+fn with_params(instr: Instr, params: &[Param]) -> Instr {
+    match instr {
+        Instr::Add(..) => Instr::Add(params[0], params[1], params[2]),
+        Instr::Mul(..) => Instr::Mul(params[0], params[1], params[2]),
+        Instr::LessThan(..) => Instr::LessThan(params[0], params[1], params[2]),
+        Instr::Equals(..) => Instr::Equals(params[0], params[1], params[2]),
+        Instr::Input(_) => Instr::Input(params[0]),
+        Instr::Output(_) => Instr::Output(params[0]),
+        Instr::AdjustRelativeBase(_) => Instr::AdjustRelativeBase(params[0]),
+        Instr::JumpIfTrue(_, t) => Instr::JumpIfTrue(params[0], t),
+        Instr::JumpIfFalse(_, t) => Instr::JumpIfFalse(params[0], t),
+    }
+}
+
+/// Smaller variations of a program, roughly from the largest reduction to the smallest.
+fn candidates(program: &Program) -> Vec<Program> {
+    let mut candidates = Vec::new();
+
+    for idx in 0..program.instrs.len() {
+        candidates.push(without_instr(program, idx));
+    }
+
+    if !program.data.is_empty() {
+        let mut candidate = program.clone();
+        candidate.data.pop();
+        candidates.push(candidate);
+    }
+
+    for idx in 0..program.input.len() {
+        let mut candidate = program.clone();
+        candidate.input.remove(idx);
+        candidates.push(candidate);
+    }
+
+    for (idx, &instr) in program.instrs.iter().enumerate() {
+        let instr_params = instr.params();
+        for (n, &p) in instr_params.iter().enumerate() {
+            for simpler in simpler_params(p) {
+                let mut new_params = instr_params.clone();
+                new_params[n] = simpler;
+                let mut candidate = program.clone();
+                candidate.instrs[idx] = with_params(instr, &new_params);
+                candidates.push(candidate);
+            }
+        }
+    }
+
+    for (idx, &v) in program.data.iter().enumerate() {
+        for simpler in simpler_values(v) {
+            let mut candidate = program.clone();
+            candidate.data[idx] = simpler;
+            candidates.push(candidate);
+        }
+    }
+
+    for (idx, &v) in program.input.iter().enumerate() {
+        for simpler in simpler_values(v) {
+            let mut candidate = program.clone();
+            candidate.input[idx] = simpler;
+            candidates.push(candidate);
+        }
+    }
+
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gen::Generator;
+    use crate::interp::{check, Interpreter, INTERPRETERS};
+    use crate::program::{Outcome, Subset};
+
+    #[test]
+    fn removing_instr_keeps_jumps_forward() {
+        let program = Program {
+            instrs: vec![
+                Instr::JumpIfTrue(Param::Immediate(1), 2),
+                Instr::Output(Param::Immediate(1)),
+                Instr::Output(Param::Immediate(2)),
+            ],
+            data: vec![],
+            input: vec![],
+        };
+
+        let candidate = without_instr(&program, 1);
+        assert_eq!(
+            vec![
+                Instr::JumpIfTrue(Param::Immediate(1), 1),
+                Instr::Output(Param::Immediate(2)),
+            ],
+            candidate.instrs
+        );
+        assert_eq!(vec![2], candidate.evaluate().unwrap().output);
+    }
+
+    fn off_by_one_output(mem_state: &[i64], input: &[i64]) -> Result<Outcome, String> {
+        let mut outcome = (INTERPRETERS[INTERPRETERS.len() - 1].exec)(mem_state, input)?;
+        if let Some(o) = outcome.output.first_mut() {
+            *o += 1;
+        }
+        Ok(outcome)
+    }
+
+    #[test]
+    fn minimizes_to_reproducer() {
+        let buggy = [Interpreter {
+            name: "off by one output",
+            subset: Subset::Day9,
+            exec: off_by_one_output,
+        }];
+
+        let mut gen = Generator::new(3);
+        let program = loop {
+            let program = gen.program(Subset::Day5);
+            if check(&program, &buggy).is_err() {
+                break program;
+            }
+        };
+
+        let minimized = minimize(&program, |p| check(p, &buggy).is_err());
+        assert_eq!(
+            Program {
+                instrs: vec![Instr::Output(Param::Immediate(0))],
+                data: vec![],
+                input: vec![],
+            },
+            minimized
+        );
+    }
+}
@@ -0,0 +1,373 @@
+use std::convert::TryFrom;
+
+/// The opcode subset supported by an interpreter.
+///
+/// Each subset includes everything in the previous subsets.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Subset {
+    /// Add and multiply with position mode parameters and non-negative values (day 2).
+    Day2,
+    /// Input, output, jumps, comparisons and immediate mode parameters (day 5).
+    Day5,
+    /// Relative base adjustment and relative mode parameters (day 9).
+    Day9,
+}
+
+/// An instruction parameter.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum Param {
+    /// A position mode parameter pointing at a cell in the data region.
+    Data(usize),
+    Immediate(i64),
+    Relative(i64),
+}
+
+impl Param {
+    fn mode(self) -> i64 {
+        match self {
+            Param::Data(_) => 0,
+            Param::Immediate(_) => 1,
+            Param::Relative(_) => 2,
+        }
+    }
+
+    fn subset(self) -> Subset {
+        match self {
+            Param::Data(_) => Subset::Day2,
+            Param::Immediate(_) => Subset::Day5,
+            Param::Relative(_) => Subset::Day9,
+        }
+    }
+}
+
+/// An instruction in a generated program.
+///
+/// Jump targets are instruction indexes which must be after the jump. A target equal to the
+/// number of instructions jumps to the final halt. Only jumping forwards guarantees every
+/// program halts.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum Instr {
+    Add(Param, Param, Param),
+    Mul(Param, Param, Param),
+    Input(Param),
+    Output(Param),
+    JumpIfTrue(Param, usize),
+    JumpIfFalse(Param, usize),
+    LessThan(Param, Param, Param),
+    Equals(Param, Param, Param),
+    AdjustRelativeBase(Param),
+}
+
+impl Instr {
+    fn len(self) -> usize {
+        match self {
+            Instr::Add(..) | Instr::Mul(..) | Instr::LessThan(..) | Instr::Equals(..) => 4,
+            Instr::JumpIfTrue(..) | Instr::JumpIfFalse(..) => 3,
+            Instr::Input(_) | Instr::Output(_) | Instr::AdjustRelativeBase(_) => 2,
+        }
+    }
+
+    pub(crate) fn params(self) -> Vec<Param> {
+        match self {
+            Instr::Add(a, b, c)
+            | Instr::Mul(a, b, c)
+            | Instr::LessThan(a, b, c)
+            | Instr::Equals(a, b, c) => vec![a, b, c],
+            Instr::JumpIfTrue(a, _) | Instr::JumpIfFalse(a, _) => vec![a],
+            Instr::Input(a) | Instr::Output(a) | Instr::AdjustRelativeBase(a) => vec![a],
+        }
+    }
+
+    fn subset(self) -> Subset {
+        let op_subset = match self {
+            Instr::Add(..) | Instr::Mul(..) => Subset::Day2,
+            Instr::AdjustRelativeBase(_) => Subset::Day9,
+            _ => Subset::Day5,
+        };
+        self.params()
+            .into_iter()
+            .map(Param::subset)
+            .fold(op_subset, Ord::max)
+    }
+}
+
+/// The final memory state and output of a halted program.
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub struct Outcome {
+    pub mem_state: Vec<i64>,
+    pub output: Vec<i64>,
+}
+
+/// A program which halts, followed by a data region, and the input it consumes.
+#[derive(Clone, Debug, Default, Hash, Eq, PartialEq)]
+pub struct Program {
+    pub instrs: Vec<Instr>,
+    pub data: Vec<i64>,
+    pub input: Vec<i64>,
+}
+
+impl Program {
+    /// The smallest subset which can run the program.
+    #[must_use]
+    pub fn subset(&self) -> Subset {
+        let value_subset = if self.data.iter().any(|&v| v < 0) {
+            Subset::Day5
+        } else {
+            Subset::Day2
+        };
+        self.instrs
+            .iter()
+            .map(|i| i.subset())
+            .fold(value_subset, Ord::max)
+    }
+
+    fn addrs(&self) -> Vec<usize> {
+        let mut addrs = Vec::with_capacity(self.instrs.len() + 1);
+        let mut addr = 0;
+        for instr in &self.instrs {
+            addrs.push(addr);
+            addr += instr.len();
+        }
+        addrs.push(addr);
+        addrs
+    }
+
+    /// The address where the data region starts.
+    #[must_use]
+    pub fn data_start(&self) -> usize {
+        self.instrs.iter().map(|i| i.len()).sum::<usize>() + 1
+    }
+
+    /// Encodes the program into its initial memory state.
+    #[must_use]
+    pub fn mem_state(&self) -> Vec<i64> {
+        let addrs = self.addrs();
+        let data_start = self.data_start();
+        let encode = |p: Param| match p {
+            Param::Data(idx) => i64::try_from(data_start + idx).unwrap(),
+            Param::Immediate(v) | Param::Relative(v) => v,
+        };
+        let target = |idx: usize| i64::try_from(addrs[idx]).unwrap();
+
+        let mut mem_state = Vec::with_capacity(data_start + self.data.len());
+        for &instr in &self.instrs {
+            let op = match instr {
+                Instr::Add(..) => 1,
+                Instr::Mul(..) => 2,
+                Instr::Input(_) => 3,
+                Instr::Output(_) => 4,
+                Instr::JumpIfTrue(..) => 5,
+                Instr::JumpIfFalse(..) => 6,
+                Instr::LessThan(..) => 7,
+                Instr::Equals(..) => 8,
+                Instr::AdjustRelativeBase(_) => 9,
+            };
+            let params = instr.params();
+            let modes = params
+                .iter()
+                .enumerate()
+                .map(|(n, p)| p.mode() * 10i64.pow(u32::try_from(n).unwrap() + 2))
+                .sum::<i64>();
+            let modes = match instr {
+                // The jump target is always an immediate parameter.
+                Instr::JumpIfTrue(..) | Instr::JumpIfFalse(..) => modes + 1000,
+                _ => modes,
+            };
+            mem_state.push(op + modes);
+            mem_state.extend(params.into_iter().map(encode));
+            match instr {
+                Instr::JumpIfTrue(_, t) | Instr::JumpIfFalse(_, t) => mem_state.push(target(t)),
+                _ => {}
+            }
+        }
+        mem_state.push(99);
+        mem_state.extend_from_slice(&self.data);
+        mem_state
+    }
+
+    /// Runs the program against a reference model.
+    ///
+    /// Returns `None` if the program is not valid: a jump which is not forwards, a read or
+    /// write out of bounds, a write into the code region, arithmetic overflow or not enough
+    /// input.
+    #[must_use]
+    pub fn evaluate(&self) -> Option<Outcome> {
+        let is_forward = self
+            .instrs
+            .iter()
+            .enumerate()
+            .all(|(pc, instr)| match instr {
+                Instr::JumpIfTrue(_, t) | Instr::JumpIfFalse(_, t) => {
+                    pc < *t && *t <= self.instrs.len()
+                }
+                _ => true,
+            });
+        if !is_forward {
+            return None;
+        }
+
+        let data_start = self.data_start();
+        let mut mem_state = self.mem_state();
+        let mut input = self.input.iter();
+        let mut output = Vec::new();
+        let mut relative_base: i64 = 0;
+        let mut pc = 0;
+
+        let addr = |p: Param, relative_base: i64| -> Option<usize> {
+            match p {
+                Param::Data(idx) => Some(data_start + idx),
+                Param::Immediate(_) => None,
+                Param::Relative(v) => usize::try_from(relative_base.checked_add(v)?).ok(),
+            }
+        };
+        let load = |mem_state: &[i64], p: Param, relative_base: i64| -> Option<i64> {
+            match p {
+                Param::Immediate(v) => Some(v),
+                _ => mem_state.get(addr(p, relative_base)?).copied(),
+            }
+        };
+        let store = |mem_state: &mut [i64], p: Param, relative_base: i64, v: i64| -> Option<()> {
+            let idx = addr(p, relative_base)?;
+            if idx < data_start {
+                return None;
+            }
+            *mem_state.get_mut(idx)? = v;
+            Some(())
+        };
+
+        while let Some(&instr) = self.instrs.get(pc) {
+            let mut next = pc + 1;
+            match instr {
+                Instr::Add(a, b, c) => {
+                    let v = load(&mem_state, a, relative_base)?.checked_add(load(
+                        &mem_state,
+                        b,
+                        relative_base,
+                    )?)?;
+                    store(&mut mem_state, c, relative_base, v)?;
+                }
+                Instr::Mul(a, b, c) => {
+                    let v = load(&mem_state, a, relative_base)?.checked_mul(load(
+                        &mem_state,
+                        b,
+                        relative_base,
+                    )?)?;
+                    store(&mut mem_state, c, relative_base, v)?;
+                }
+                Instr::Input(a) => {
+                    let v = *input.next()?;
+                    store(&mut mem_state, a, relative_base, v)?;
+                }
+                Instr::Output(a) => output.push(load(&mem_state, a, relative_base)?),
+                Instr::JumpIfTrue(a, t) | Instr::JumpIfFalse(a, t) => {
+                    let v = load(&mem_state, a, relative_base)?;
+                    if (v != 0) == matches!(instr, Instr::JumpIfTrue(..)) {
+                        next = t;
+                    }
+                }
+                Instr::LessThan(a, b, c) => {
+                    let v =
+                        load(&mem_state, a, relative_base)? < load(&mem_state, b, relative_base)?;
+                    store(&mut mem_state, c, relative_base, i64::from(v))?;
+                }
+                Instr::Equals(a, b, c) => {
+                    let v =
+                        load(&mem_state, a, relative_base)? == load(&mem_state, b, relative_base)?;
+                    store(&mut mem_state, c, relative_base, i64::from(v))?;
+                }
+                Instr::AdjustRelativeBase(a) => {
+                    relative_base =
+                        relative_base.checked_add(load(&mem_state, a, relative_base)?)?;
+                }
+            }
+            pc = next;
+        }
+
+        Some(Outcome { mem_state, output })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_day2() {
+        let program = Program {
+            instrs: vec![Instr::Add(Param::Data(0), Param::Data(1), Param::Data(2))],
+            data: vec![30, 40, 0],
+            input: vec![],
+        };
+
+        assert_eq!(Subset::Day2, program.subset());
+        assert_eq!(vec![1, 5, 6, 7, 99, 30, 40, 0], program.mem_state());
+        assert_eq!(
+            Some(Outcome {
+                mem_state: vec![1, 5, 6, 7, 99, 30, 40, 70],
+                output: vec![],
+            }),
+            program.evaluate()
+        );
+    }
+
+    #[test]
+    fn encode_modes_and_jumps() {
+        let program = Program {
+            instrs: vec![
+                Instr::Input(Param::Data(0)),
+                Instr::JumpIfFalse(Param::Data(0), 3),
+                Instr::Output(Param::Immediate(7)),
+                Instr::AdjustRelativeBase(Param::Immediate(1)),
+                Instr::Output(Param::Relative(0)),
+            ],
+            data: vec![5],
+            input: vec![0],
+        };
+
+        assert_eq!(Subset::Day9, program.subset());
+        assert_eq!(
+            vec![3, 12, 1006, 12, 7, 104, 7, 109, 1, 204, 0, 99, 5],
+            program.mem_state()
+        );
+        assert_eq!(vec![12], program.evaluate().unwrap().output);
+    }
+
+    #[test]
+    fn invalid_programs() {
+        let write_to_code = Program {
+            instrs: vec![Instr::Add(
+                Param::Data(0),
+                Param::Data(0),
+                Param::Relative(0),
+            )],
+            data: vec![1],
+            input: vec![],
+        };
+        assert_eq!(None, write_to_code.evaluate());
+
+        let backwards_jump = Program {
+            instrs: vec![Instr::JumpIfTrue(Param::Immediate(1), 0)],
+            data: vec![],
+            input: vec![],
+        };
+        assert_eq!(None, backwards_jump.evaluate());
+
+        let overflow = Program {
+            instrs: vec![Instr::Mul(
+                Param::Immediate(i64::MAX),
+                Param::Immediate(2),
+                Param::Data(0),
+            )],
+            data: vec![0],
+            input: vec![],
+        };
+        assert_eq!(None, overflow.evaluate());
+
+        let missing_input = Program {
+            instrs: vec![Instr::Input(Param::Data(0))],
+            data: vec![0],
+            input: vec![],
+        };
+        assert_eq!(None, missing_input.evaluate());
+    }
+}
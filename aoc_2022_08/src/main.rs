@@ -1,104 +1,42 @@
-use itertools::{FoldWhile, Itertools as _};
-use std::{cmp::Ordering, io};
+use aoc_grid::{Dir4, Grid, Point2};
+use std::io;
 
-fn is_visible_from_edge(x: usize, y: usize, grid: &[Vec<u32>]) -> bool {
-    let row = &grid[y];
-    let min_height = row[x];
+fn is_visible_from_edge(p: Point2, grid: &Grid<u32>) -> bool {
+    let min_height = grid[p];
 
-    // Left
-    if !row.iter().take(x).any(|h| *h >= min_height) {
-        return false;
-    }
-    // Right
-    if !row.iter().skip(x + 1).any(|h| *h >= min_height) {
-        return false;
-    }
-    // Up
-    if !grid.iter().take(y).any(|row| row[x] >= min_height) {
-        return false;
-    }
-    // Down
-    if !grid.iter().skip(y + 1).any(|row| row[x] >= min_height) {
-        return false;
-    }
-
-    true
-}
-
-fn scenic_score(x: usize, y: usize, grid: &[Vec<u32>]) -> u32 {
-    let row = &grid[y];
-    let min_height = row[x];
-
-    // Left
-    let left_score = row
+    Dir4::ALL
         .iter()
-        .rev()
-        .skip(row.len() - x)
-        .fold_while(0, |acc, h| match h.cmp(&min_height) {
-            Ordering::Greater | Ordering::Equal => FoldWhile::Done(acc + 1),
-            Ordering::Less => FoldWhile::Continue(acc + 1),
-        })
-        .into_inner();
+        .all(|&dir| grid.ray(p, dir).any(|(_, h)| *h >= min_height))
+}
 
-    // Right
-    let right_score = row
-        .iter()
-        .skip(x + 1)
-        .fold_while(0, |acc, h| match h.cmp(&min_height) {
-            Ordering::Greater | Ordering::Equal => FoldWhile::Done(acc + 1),
-            Ordering::Less => FoldWhile::Continue(acc + 1),
-        })
-        .into_inner();
+fn viewing_distance(p: Point2, dir: Dir4, grid: &Grid<u32>) -> u32 {
+    let min_height = grid[p];
 
-    // Up
-    let up_score = grid
-        .iter()
-        .rev()
-        .skip(grid.len() - y)
-        .fold_while(0, |acc, row| {
-            let h = row[x];
-            match h.cmp(&min_height) {
-                Ordering::Greater | Ordering::Equal => FoldWhile::Done(acc + 1),
-                Ordering::Less => FoldWhile::Continue(acc + 1),
-            }
-        })
-        .into_inner();
+    let mut distance = 0;
+    for (_, h) in grid.ray(p, dir) {
+        distance += 1;
+        if *h >= min_height {
+            break;
+        }
+    }
+    distance
+}
 
-    // Down
-    let down_score = grid
+fn scenic_score(p: Point2, grid: &Grid<u32>) -> u32 {
+    Dir4::ALL
         .iter()
-        .skip(y + 1)
-        .fold_while(0, |acc, row| {
-            let h = row[x];
-            match h.cmp(&min_height) {
-                Ordering::Greater | Ordering::Equal => FoldWhile::Done(acc + 1),
-                Ordering::Less => FoldWhile::Continue(acc + 1),
-            }
-        })
-        .into_inner();
-
-    left_score * right_score * up_score * down_score
+        .map(|&dir| viewing_distance(p, dir, grid))
+        .product()
 }
 
 fn main() -> io::Result<()> {
-    let grid = itertools::process_results(io::stdin().lines(), |it| {
-        it.map(|line| {
-            line.chars()
-                .map(|ch| {
-                    ch.to_digit(10)
-                        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid number"))
-                })
-                .collect::<Result<Vec<u32>, _>>()
-        })
-        .collect::<Result<Vec<_>, _>>()
-    })??;
-
-    let row_len = grid[0].len();
-
-    assert!(grid.iter().all(|row| row.len() == row_len));
+    let input = io::read_to_string(io::stdin())?;
+    let grid = Grid::parse_with(&input, |ch| ch.to_digit(10))
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
 
-    let answer = (0..grid.len())
-        .filter_map(|y| (0..row_len).map(|x| scenic_score(x, y, &grid)).max())
+    let answer = grid
+        .points()
+        .map(|p| scenic_score(p, &grid))
         .max()
         .expect("grid is empty");
 
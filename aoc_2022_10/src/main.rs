@@ -51,6 +51,7 @@ fn main() -> io::Result<()> {
     itertools::process_results(io::stdin().lines(), |lines| {
         let mut cpu = Cpu::default();
         let mut pos = 0;
+        let mut pixels = Vec::new();
 
         for line in lines {
             let (remaining, inst) = parse_line(&line)
@@ -58,7 +59,9 @@ fn main() -> io::Result<()> {
             assert_eq!("", remaining);
 
             for _ in 0..inst.num_cycles() {
-                if (cpu.reg_x - 1..=cpu.reg_x + 1).contains(&pos) {
+                let lit = (cpu.reg_x - 1..=cpu.reg_x + 1).contains(&pos);
+                pixels.push(lit);
+                if lit {
                     print!("#");
                 } else {
                     print!(".");
@@ -77,6 +80,10 @@ fn main() -> io::Result<()> {
             }
         }
 
+        let text = aoc_ocr::recognize(&pixels, 40)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        println!("{text}");
+
         Ok::<_, io::Error>(())
     })??;
 
@@ -0,0 +1,170 @@
+use crate::point::Point2;
+
+/// An orthogonal direction.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum Dir4 {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Dir4 {
+    /// Every direction, clockwise from `Up`.
+    pub const ALL: [Dir4; 4] = [Dir4::Up, Dir4::Right, Dir4::Down, Dir4::Left];
+
+    fn from_index(i: usize) -> Self {
+        Self::ALL[i % 4]
+    }
+
+    fn index(self) -> usize {
+        match self {
+            Dir4::Up => 0,
+            Dir4::Right => 1,
+            Dir4::Down => 2,
+            Dir4::Left => 3,
+        }
+    }
+
+    #[must_use]
+    pub fn turn_left(self) -> Self {
+        Self::from_index(self.index() + 3)
+    }
+
+    #[must_use]
+    pub fn turn_right(self) -> Self {
+        Self::from_index(self.index() + 1)
+    }
+
+    #[must_use]
+    pub fn reverse(self) -> Self {
+        Self::from_index(self.index() + 2)
+    }
+
+    /// The change in position for one step.
+    #[must_use]
+    pub fn offset(self) -> Point2 {
+        Dir8::from(self).offset()
+    }
+}
+
+/// An orthogonal or diagonal direction.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum Dir8 {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+impl Dir8 {
+    /// Every direction, clockwise from `North`.
+    pub const ALL: [Dir8; 8] = [
+        Dir8::North,
+        Dir8::NorthEast,
+        Dir8::East,
+        Dir8::SouthEast,
+        Dir8::South,
+        Dir8::SouthWest,
+        Dir8::West,
+        Dir8::NorthWest,
+    ];
+
+    fn from_index(i: usize) -> Self {
+        Self::ALL[i % 8]
+    }
+
+    fn index(self) -> usize {
+        match self {
+            Dir8::North => 0,
+            Dir8::NorthEast => 1,
+            Dir8::East => 2,
+            Dir8::SouthEast => 3,
+            Dir8::South => 4,
+            Dir8::SouthWest => 5,
+            Dir8::West => 6,
+            Dir8::NorthWest => 7,
+        }
+    }
+
+    /// Turns 45 degrees counterclockwise.
+    #[must_use]
+    pub fn turn_left(self) -> Self {
+        Self::from_index(self.index() + 7)
+    }
+
+    /// Turns 45 degrees clockwise.
+    #[must_use]
+    pub fn turn_right(self) -> Self {
+        Self::from_index(self.index() + 1)
+    }
+
+    #[must_use]
+    pub fn reverse(self) -> Self {
+        Self::from_index(self.index() + 4)
+    }
+
+    /// The change in position for one step.
+    #[must_use]
+    pub fn offset(self) -> Point2 {
+        let (x, y) = match self {
+            Dir8::North => (0, -1),
+            Dir8::NorthEast => (1, -1),
+            Dir8::East => (1, 0),
+            Dir8::SouthEast => (1, 1),
+            Dir8::South => (0, 1),
+            Dir8::SouthWest => (-1, 1),
+            Dir8::West => (-1, 0),
+            Dir8::NorthWest => (-1, -1),
+        };
+        Point2::new(x, y)
+    }
+}
+
+impl From<Dir4> for Dir8 {
+    fn from(dir: Dir4) -> Self {
+        match dir {
+            Dir4::Up => Dir8::North,
+            Dir4::Right => Dir8::East,
+            Dir4::Down => Dir8::South,
+            Dir4::Left => Dir8::West,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dir4_turns() {
+        assert_eq!(Dir4::Left, Dir4::Up.turn_left());
+        assert_eq!(Dir4::Right, Dir4::Up.turn_right());
+        assert_eq!(Dir4::Down, Dir4::Up.reverse());
+        for d in Dir4::ALL.iter().copied() {
+            assert_eq!(d, d.turn_left().turn_right());
+            assert_eq!(d.reverse(), d.turn_right().turn_right());
+            assert_eq!(-d.offset(), d.reverse().offset());
+        }
+        assert_eq!(Point2::new(0, -1), Dir4::Up.offset());
+    }
+
+    #[test]
+    fn dir8_turns() {
+        assert_eq!(Dir8::NorthWest, Dir8::North.turn_left());
+        assert_eq!(Dir8::NorthEast, Dir8::North.turn_right());
+        for d in Dir8::ALL.iter().copied() {
+            assert_eq!(d, d.turn_left().turn_right());
+            assert_eq!(
+                d.reverse(),
+                d.turn_right().turn_right().turn_right().turn_right()
+            );
+            assert_eq!(-d.offset(), d.reverse().offset());
+        }
+        assert_eq!(Dir8::East, Dir8::from(Dir4::Right));
+    }
+}
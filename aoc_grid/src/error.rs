@@ -0,0 +1,43 @@
+use std::{
+    error,
+    fmt::{self, Debug, Display},
+};
+
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub enum Error {
+    /// The number of cells is not a multiple of the width.
+    InvalidDimensions,
+    /// A line in a character map has a different length than the first line.
+    RaggedLine {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// A character in a character map is not recognized.
+    UnknownChar {
+        line: usize,
+        column: usize,
+        ch: char,
+    },
+}
+
+impl error::Error for Error {}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidDimensions => f.write_str("cells do not fill the width"),
+            Error::RaggedLine {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {line}: expected {expected} characters, found {found}"
+            ),
+            Error::UnknownChar { line, column, ch } => {
+                write!(f, "line {line}, column {column}: unknown character {ch:?}")
+            }
+        }
+    }
+}
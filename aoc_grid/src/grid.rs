@@ -0,0 +1,323 @@
+use std::fmt::{self, Display};
+use std::ops::{Index, IndexMut};
+use std::str::FromStr;
+
+use crate::dir::Dir8;
+use crate::error::Error;
+use crate::point::Point2;
+
+/// A rectangle of cells with the top left cell at the origin.
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    #[must_use]
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid from cells in row-major order.
+    pub fn from_vec(width: usize, cells: Vec<T>) -> Result<Self, Error> {
+        let height = match width {
+            0 if cells.is_empty() => 0,
+            0 => return Err(Error::InvalidDimensions),
+            _ if !cells.len().is_multiple_of(width) => return Err(Error::InvalidDimensions),
+            _ => cells.len() / width,
+        };
+        Ok(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    pub fn from_fn<F: FnMut(Point2) -> T>(width: usize, height: usize, f: F) -> Self {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| point(x, y)))
+            .map(f)
+            .collect();
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Parses a character map, converting each character with `f`.
+    ///
+    /// Every line must be the same length. `f` returns `None` for an unknown character.
+    pub fn parse_with<F: FnMut(char) -> Option<T>>(s: &str, mut f: F) -> Result<Self, Error> {
+        let mut width = None;
+        let mut cells = Vec::new();
+        for (i, line) in s.lines().enumerate() {
+            let start = cells.len();
+            for (column, ch) in line.chars().enumerate() {
+                cells.push(f(ch).ok_or(Error::UnknownChar {
+                    line: i + 1,
+                    column: column + 1,
+                    ch,
+                })?);
+            }
+            let found = cells.len() - start;
+            match width {
+                None => width = Some(found),
+                Some(expected) if expected != found => {
+                    return Err(Error::RaggedLine {
+                        line: i + 1,
+                        expected,
+                        found,
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        Self::from_vec(width.unwrap_or(0), cells)
+    }
+
+    #[must_use]
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[must_use]
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn index_of(&self, p: Point2) -> Option<usize> {
+        let x = usize::try_from(p.x).ok()?;
+        let y = usize::try_from(p.y).ok()?;
+        if x < self.width && y < self.height {
+            Some(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    #[must_use]
+    pub fn contains(&self, p: Point2) -> bool {
+        self.index_of(p).is_some()
+    }
+
+    #[must_use]
+    pub fn get(&self, p: Point2) -> Option<&T> {
+        self.index_of(p).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, p: Point2) -> Option<&mut T> {
+        self.index_of(p).map(move |i| &mut self.cells[i])
+    }
+
+    /// Every point in the grid, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point2> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| point(x, y)))
+    }
+
+    /// Every cell with its point, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point2, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    /// The cells in row `y`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `y` is not less than the height.
+    #[must_use]
+    pub fn row(&self, y: usize) -> &[T] {
+        assert!(y < self.height, "row {y} out of bounds");
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` panics on a zero width, which only happens when there are no cells.
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// The cells in column `x`, from top to bottom.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` is not less than the width.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.width, "column {x} out of bounds");
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    /// The cells from `from` (exclusive) in the direction until the edge of the grid.
+    pub fn ray<D: Into<Dir8>>(&self, from: Point2, dir: D) -> impl Iterator<Item = (Point2, &T)> {
+        let offset = dir.into().offset();
+        let mut p = from;
+        std::iter::from_fn(move || {
+            p += offset;
+            self.get(p).map(|v| (p, v))
+        })
+    }
+
+    /// The orthogonally adjacent cells within the grid.
+    pub fn neighbors4(&self, p: Point2) -> impl Iterator<Item = (Point2, &T)> {
+        p.neighbors4()
+            .filter_map(move |n| self.get(n).map(|v| (n, v)))
+    }
+
+    /// The orthogonally and diagonally adjacent cells within the grid.
+    pub fn neighbors8(&self, p: Point2) -> impl Iterator<Item = (Point2, &T)> {
+        p.neighbors8()
+            .filter_map(move |n| self.get(n).map(|v| (n, v)))
+    }
+
+    /// Converts every cell.
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+fn point(x: usize, y: usize) -> Point2 {
+    Point2::new(
+        i64::try_from(x).expect("x to fit in i64"),
+        i64::try_from(y).expect("y to fit in i64"),
+    )
+}
+
+impl<T> Index<Point2> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, p: Point2) -> &T {
+        self.get(p).unwrap_or_else(|| panic!("{p:?} out of bounds"))
+    }
+}
+
+impl<T> IndexMut<Point2> for Grid<T> {
+    fn index_mut(&mut self, p: Point2) -> &mut T {
+        self.get_mut(p)
+            .unwrap_or_else(|| panic!("{p:?} out of bounds"))
+    }
+}
+
+impl FromStr for Grid<char> {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(s, Some)
+    }
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for cell in row {
+                Display::fmt(cell, f)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dir::Dir4;
+
+    const MAP: &str = "\
+30373
+25512
+65332
+33549
+35390";
+
+    fn digits() -> Grid<u32> {
+        Grid::parse_with(MAP, |ch| ch.to_digit(10)).unwrap()
+    }
+
+    #[test]
+    fn parse() {
+        let grid = digits();
+        assert_eq!(5, grid.width());
+        assert_eq!(5, grid.height());
+        assert_eq!(7, grid[Point2::new(3, 0)]);
+        assert_eq!(Some(&9), grid.get(Point2::new(4, 3)));
+        assert_eq!(None, grid.get(Point2::new(5, 0)));
+        assert_eq!(None, grid.get(Point2::new(0, -1)));
+        assert_eq!(MAP, grid.to_string().trim_end());
+
+        assert_eq!(
+            Err(Error::RaggedLine {
+                line: 2,
+                expected: 2,
+                found: 3,
+            }),
+            "ab\ncde".parse::<Grid<char>>()
+        );
+        assert_eq!(
+            Err(Error::UnknownChar {
+                line: 2,
+                column: 1,
+                ch: 'x',
+            }),
+            Grid::parse_with("12\nx3", |ch| ch.to_digit(10))
+        );
+        assert_eq!(0, "".parse::<Grid<char>>().unwrap().points().count());
+    }
+
+    #[test]
+    fn rows_columns_and_rays() {
+        let grid = digits();
+        assert_eq!(&[2, 5, 5, 1, 2], grid.row(1));
+        assert_eq!(5, grid.rows().count());
+        assert_eq!(
+            vec![3, 2, 6, 3, 3],
+            grid.column(0).copied().collect::<Vec<_>>()
+        );
+
+        let center = Point2::new(2, 3);
+        let values = |dir: Dir8| grid.ray(center, dir).map(|(_, v)| *v).collect::<Vec<_>>();
+        assert_eq!(vec![3, 5, 3], values(Dir8::North));
+        assert_eq!(vec![3], values(Dir8::South));
+        assert_eq!(vec![3, 3], values(Dir8::West));
+        assert_eq!(vec![3, 2], values(Dir8::NorthEast));
+        assert_eq!(
+            Some(Point2::new(4, 3)),
+            grid.ray(center, Dir4::Right).last().map(|(p, _)| p)
+        );
+    }
+
+    #[test]
+    fn neighbors_and_updates() {
+        let mut grid = digits();
+        assert_eq!(2, grid.neighbors4(Point2::ORIGIN).count());
+        assert_eq!(3, grid.neighbors8(Point2::ORIGIN).count());
+        assert_eq!(8, grid.neighbors8(Point2::new(1, 1)).count());
+
+        grid[Point2::new(1, 1)] = 0;
+        *grid.get_mut(Point2::new(2, 2)).unwrap() += 1;
+        assert_eq!(0, grid[Point2::new(1, 1)]);
+        assert_eq!(4, grid[Point2::new(2, 2)]);
+        assert_eq!(None, grid.get_mut(Point2::new(-1, 0)));
+
+        let tall = grid.map(|&h| h >= 5);
+        assert_eq!(8, tall.iter().filter(|(_, &t)| t).count());
+
+        let squares = Grid::from_fn(3, 2, |p| p.x * p.y);
+        assert_eq!(Ok(squares), Grid::from_vec(3, vec![0, 0, 0, 0, 1, 2]));
+        assert_eq!(
+            Err(Error::InvalidDimensions),
+            Grid::from_vec(2, vec![1, 2, 3])
+        );
+    }
+}
@@ -0,0 +1,15 @@
+//! Points, directions and grids shared by the puzzles.
+//!
+//! `y` increases downward, so the first line of a character map is row 0 and `Dir4::Up` decreases
+//! `y`.
+
+pub mod dir;
+pub mod error;
+pub mod grid;
+pub mod point;
+pub mod sparse;
+
+pub use dir::{Dir4, Dir8};
+pub use grid::Grid;
+pub use point::{Bounds, Point2};
+pub use sparse::SparseGrid;
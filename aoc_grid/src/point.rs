@@ -0,0 +1,193 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use crate::dir::{Dir4, Dir8};
+
+#[derive(Copy, Clone, Debug, Default, Hash, Eq, Ord, PartialEq, PartialOrd)]
+pub struct Point2 {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl Point2 {
+    pub const ORIGIN: Point2 = Point2 { x: 0, y: 0 };
+
+    #[must_use]
+    pub const fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+
+    #[must_use]
+    pub fn manhattan(self, other: Point2) -> u64 {
+        (self.x - other.x).unsigned_abs() + (self.y - other.y).unsigned_abs()
+    }
+
+    #[must_use]
+    pub fn chebyshev(self, other: Point2) -> u64 {
+        (self.x - other.x)
+            .unsigned_abs()
+            .max((self.y - other.y).unsigned_abs())
+    }
+
+    /// The point one step away in the direction.
+    #[must_use]
+    pub fn step<D: Into<Dir8>>(self, dir: D) -> Self {
+        self + dir.into().offset()
+    }
+
+    /// The four orthogonally adjacent points, in `Dir4::ALL` order.
+    pub fn neighbors4(self) -> impl Iterator<Item = Point2> {
+        Dir4::ALL.iter().map(move |&d| self.step(d))
+    }
+
+    /// The eight adjacent points, in `Dir8::ALL` order.
+    pub fn neighbors8(self) -> impl Iterator<Item = Point2> {
+        Dir8::ALL.iter().map(move |&d| self.step(d))
+    }
+}
+
+impl From<(i64, i64)> for Point2 {
+    fn from((x, y): (i64, i64)) -> Self {
+        Self { x, y }
+    }
+}
+
+impl Add for Point2 {
+    type Output = Point2;
+
+    fn add(self, other: Point2) -> Point2 {
+        Point2::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl AddAssign for Point2 {
+    fn add_assign(&mut self, other: Point2) {
+        *self = *self + other;
+    }
+}
+
+impl Sub for Point2 {
+    type Output = Point2;
+
+    fn sub(self, other: Point2) -> Point2 {
+        Point2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl SubAssign for Point2 {
+    fn sub_assign(&mut self, other: Point2) {
+        *self = *self - other;
+    }
+}
+
+impl Mul<i64> for Point2 {
+    type Output = Point2;
+
+    fn mul(self, n: i64) -> Point2 {
+        Point2::new(self.x * n, self.y * n)
+    }
+}
+
+impl Neg for Point2 {
+    type Output = Point2;
+
+    fn neg(self) -> Point2 {
+        Point2::new(-self.x, -self.y)
+    }
+}
+
+/// An inclusive rectangle of points.
+#[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct Bounds {
+    pub min: Point2,
+    pub max: Point2,
+}
+
+impl Bounds {
+    /// The bounds containing only the point.
+    #[must_use]
+    pub fn new(p: Point2) -> Self {
+        Self { min: p, max: p }
+    }
+
+    /// The smallest bounds containing all of the points, or `None` if there are none.
+    pub fn from_points<I: IntoIterator<Item = Point2>>(points: I) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(points.fold(Bounds::new(first), |mut b, p| {
+            b.include(p);
+            b
+        }))
+    }
+
+    /// Grows the bounds to contain the point.
+    pub fn include(&mut self, p: Point2) {
+        self.min.x = self.min.x.min(p.x);
+        self.min.y = self.min.y.min(p.y);
+        self.max.x = self.max.x.max(p.x);
+        self.max.y = self.max.y.max(p.y);
+    }
+
+    #[must_use]
+    pub fn contains(&self, p: Point2) -> bool {
+        (self.min.x..=self.max.x).contains(&p.x) && (self.min.y..=self.max.y).contains(&p.y)
+    }
+
+    #[must_use]
+    pub fn width(&self) -> u64 {
+        (self.max.x - self.min.x).unsigned_abs() + 1
+    }
+
+    #[must_use]
+    pub fn height(&self) -> u64 {
+        (self.max.y - self.min.y).unsigned_abs() + 1
+    }
+
+    /// Every point within the bounds, row by row.
+    pub fn points(&self) -> impl Iterator<Item = Point2> {
+        let (min, max) = (self.min, self.max);
+        (min.y..=max.y).flat_map(move |y| (min.x..=max.x).map(move |x| Point2::new(x, y)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distances() {
+        let a = Point2::new(1, -2);
+        let b = Point2::new(-3, 5);
+        assert_eq!(11, a.manhattan(b));
+        assert_eq!(7, a.chebyshev(b));
+        assert_eq!(0, a.manhattan(a));
+        assert_eq!(Point2::new(-4, 7), b - a);
+        assert_eq!(Point2::new(2, -4), a * 2);
+    }
+
+    #[test]
+    fn neighbors() {
+        let p = Point2::new(2, 3);
+        assert_eq!(
+            vec![(2, 2), (3, 3), (2, 4), (1, 3)],
+            p.neighbors4().map(|p| (p.x, p.y)).collect::<Vec<_>>()
+        );
+        assert_eq!(8, p.neighbors8().count());
+        assert!(p.neighbors8().all(|n| n.chebyshev(p) == 1));
+        assert_eq!(Point2::new(3, 2), p.step(Dir8::NorthEast));
+    }
+
+    #[test]
+    fn bounds() {
+        assert_eq!(None, Bounds::from_points(Vec::new()));
+
+        let b = Bounds::from_points(vec![Point2::new(1, 1), Point2::new(-2, 3)]).unwrap();
+        assert_eq!(Point2::new(-2, 1), b.min);
+        assert_eq!(Point2::new(1, 3), b.max);
+        assert_eq!(4, b.width());
+        assert_eq!(3, b.height());
+        assert!(b.contains(Point2::new(0, 2)));
+        assert!(!b.contains(Point2::new(0, 0)));
+        assert_eq!(12, b.points().count());
+        assert_eq!(Some(Point2::new(-2, 1)), b.points().next());
+    }
+}
@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+
+use crate::error::Error;
+use crate::grid::Grid;
+use crate::point::{Bounds, Point2};
+
+/// Cells at arbitrary points, tracking the bounds of the points with cells.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point2, T>,
+    bounds: Option<Bounds>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SparseGrid<T> {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            cells: HashMap::new(),
+            bounds: None,
+        }
+    }
+
+    /// Parses a character map, keeping the cells for which `f` returns `Some`.
+    ///
+    /// The first line is row 0. Lines may have different lengths.
+    pub fn parse_with<F: FnMut(char) -> Option<T>>(s: &str, mut f: F) -> Self {
+        let mut grid = Self::new();
+        for (y, line) in (0..).zip(s.lines()) {
+            for (x, ch) in (0..).zip(line.chars()) {
+                if let Some(v) = f(ch) {
+                    grid.insert(Point2::new(x, y), v);
+                }
+            }
+        }
+        grid
+    }
+
+    /// The smallest bounds containing every cell, or `None` if there are no cells.
+    #[must_use]
+    pub fn bounds(&self) -> Option<Bounds> {
+        self.bounds
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    #[must_use]
+    pub fn get(&self, p: Point2) -> Option<&T> {
+        self.cells.get(&p)
+    }
+
+    pub fn get_mut(&mut self, p: Point2) -> Option<&mut T> {
+        self.cells.get_mut(&p)
+    }
+
+    /// Sets the cell, returning the previous value.
+    pub fn insert(&mut self, p: Point2, v: T) -> Option<T> {
+        match &mut self.bounds {
+            Some(b) => b.include(p),
+            None => self.bounds = Some(Bounds::new(p)),
+        }
+        self.cells.insert(p, v)
+    }
+
+    /// Removes the cell, shrinking the bounds if it was on an edge.
+    pub fn remove(&mut self, p: Point2) -> Option<T> {
+        let v = self.cells.remove(&p)?;
+        if let Some(b) = self.bounds {
+            if p.x == b.min.x || p.x == b.max.x || p.y == b.min.y || p.y == b.max.y {
+                self.bounds = Bounds::from_points(self.cells.keys().copied());
+            }
+        }
+        Some(v)
+    }
+
+    /// Every cell with its point, in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (Point2, &T)> {
+        self.cells.iter().map(|(p, v)| (*p, v))
+    }
+
+    /// Copies the cells into a dense grid covering the bounds, filling the points without cells.
+    ///
+    /// The dense grid's origin is the bounds' minimum point.
+    pub fn to_grid(&self, fill: T) -> Result<Grid<T>, Error>
+    where
+        T: Clone,
+    {
+        let b = match self.bounds {
+            Some(b) => b,
+            None => return Grid::from_vec(0, Vec::new()),
+        };
+        let cells = b
+            .points()
+            .map(|p| self.cells.get(&p).unwrap_or(&fill).clone())
+            .collect();
+        let width = usize::try_from(b.width()).map_err(|_| Error::InvalidDimensions)?;
+        Grid::from_vec(width, cells)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds_follow_cells() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(None, grid.bounds());
+
+        grid.insert(Point2::new(0, 0), 'a');
+        grid.insert(Point2::new(-3, 2), 'b');
+        grid.insert(Point2::new(4, -1), 'c');
+        assert_eq!(None, grid.insert(Point2::new(1, 1), 'd'));
+        assert_eq!(Some('a'), grid.insert(Point2::new(0, 0), 'e'));
+        assert_eq!(4, grid.len());
+        assert_eq!(
+            Some(Bounds {
+                min: Point2::new(-3, -1),
+                max: Point2::new(4, 2),
+            }),
+            grid.bounds()
+        );
+
+        assert_eq!(Some('d'), grid.remove(Point2::new(1, 1)));
+        assert_eq!(Point2::new(-3, -1), grid.bounds().unwrap().min);
+        assert_eq!(Some('c'), grid.remove(Point2::new(4, -1)));
+        assert_eq!(
+            Some(Bounds {
+                min: Point2::new(-3, 0),
+                max: Point2::new(0, 2),
+            }),
+            grid.bounds()
+        );
+        assert_eq!(None, grid.remove(Point2::new(4, -1)));
+
+        grid.remove(Point2::new(0, 0));
+        grid.remove(Point2::new(-3, 2));
+        assert!(grid.is_empty());
+        assert_eq!(None, grid.bounds());
+    }
+
+    #[test]
+    fn parse_and_densify() {
+        let grid =
+            SparseGrid::parse_with("..#\n\n#..#", |ch| if ch == '#' { Some(()) } else { None });
+        assert_eq!(3, grid.len());
+        assert_eq!(Some(&()), grid.get(Point2::new(3, 2)));
+        assert_eq!(
+            Some(Bounds {
+                min: Point2::new(0, 0),
+                max: Point2::new(3, 2),
+            }),
+            grid.bounds()
+        );
+
+        let mut grid = SparseGrid::new();
+        grid.insert(Point2::new(-1, 5), '#');
+        grid.insert(Point2::new(1, 6), '#');
+        let dense = grid.to_grid('.').unwrap();
+        assert_eq!("#..\n..#\n", dense.to_string());
+        assert_eq!(0, SparseGrid::new().to_grid('.').unwrap().width());
+    }
+}
@@ -0,0 +1,40 @@
+use std::{
+    error,
+    fmt::{self, Debug, Display},
+};
+
+/// A glyph which did not match any letter in the font.
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub struct UnknownGlyph {
+    /// The position of the glyph in the text.
+    pub index: usize,
+    /// The glyph's pixels, with `#` for lit pixels and `.` for unlit pixels.
+    pub pixels: String,
+}
+
+#[derive(Clone, Debug, Hash, Eq, PartialEq)]
+pub enum Error {
+    /// The number of pixels is not a multiple of the width.
+    InvalidDimensions,
+    /// The lit pixels do not fit either font's height.
+    UnsupportedHeight(usize),
+    UnknownGlyphs(Vec<UnknownGlyph>),
+}
+
+impl error::Error for Error {}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::InvalidDimensions => f.write_str("pixels do not fill the width"),
+            Error::UnsupportedHeight(h) => write!(f, "unsupported glyph height: {h}"),
+            Error::UnknownGlyphs(glyphs) => {
+                write!(f, "unknown glyphs at")?;
+                for g in glyphs {
+                    write!(f, " {}", g.index)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
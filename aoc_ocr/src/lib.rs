@@ -0,0 +1,418 @@
+//! Recognizes the letters drawn by Advent of Code puzzles.
+//!
+//! Supports the 4x6 font (letters 5 pixels apart) and the 6x10 font (letters 8 pixels apart).
+
+pub mod error;
+
+use error::{Error, UnknownGlyph};
+
+struct Font {
+    height: usize,
+    pitch: usize,
+    glyphs: &'static [(char, &'static [&'static str])],
+}
+
+const SMALL: Font = Font {
+    height: 6,
+    pitch: 5,
+    glyphs: &[
+        ('A', &[".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+        ('B', &["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+        ('C', &[".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+        ('E', &["####", "#...", "###.", "#...", "#...", "####"]),
+        ('F', &["####", "#...", "###.", "#...", "#...", "#..."]),
+        ('G', &[".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+        ('H', &["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+        ('I', &[".###", "..#.", "..#.", "..#.", "..#.", ".###"]),
+        ('J', &["..##", "...#", "...#", "...#", "#..#", ".##."]),
+        ('K', &["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+        ('L', &["#...", "#...", "#...", "#...", "#...", "####"]),
+        ('O', &[".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+        ('P', &["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+        ('R', &["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+        ('S', &[".###", "#...", "#...", ".##.", "...#", "###."]),
+        ('U', &["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+        ('Y', &["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+        ('Z', &["####", "...#", "..#.", ".#..", "#...", "####"]),
+    ],
+};
+
+const LARGE: Font = Font {
+    height: 10,
+    pitch: 8,
+    glyphs: &[
+        (
+            'A',
+            &[
+                "..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#",
+                "#....#", "#....#",
+            ],
+        ),
+        (
+            'B',
+            &[
+                "#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#",
+                "#....#", "#####.",
+            ],
+        ),
+        (
+            'C',
+            &[
+                ".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....",
+                "#....#", ".####.",
+            ],
+        ),
+        (
+            'E',
+            &[
+                "######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....",
+                "#.....", "######",
+            ],
+        ),
+        (
+            'F',
+            &[
+                "######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....",
+                "#.....", "#.....",
+            ],
+        ),
+        (
+            'G',
+            &[
+                ".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#",
+                "#...##", ".###.#",
+            ],
+        ),
+        (
+            'H',
+            &[
+                "#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#",
+                "#....#", "#....#",
+            ],
+        ),
+        (
+            'J',
+            &[
+                "...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.",
+                "#...#.", ".###..",
+            ],
+        ),
+        (
+            'K',
+            &[
+                "#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..",
+                "#...#.", "#....#",
+            ],
+        ),
+        (
+            'L',
+            &[
+                "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....",
+                "#.....", "######",
+            ],
+        ),
+        (
+            'N',
+            &[
+                "#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##",
+                "#...##", "#....#",
+            ],
+        ),
+        (
+            'P',
+            &[
+                "#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....",
+                "#.....", "#.....",
+            ],
+        ),
+        (
+            'R',
+            &[
+                "#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.",
+                "#....#", "#....#",
+            ],
+        ),
+        (
+            'X',
+            &[
+                "#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.",
+                "#....#", "#....#",
+            ],
+        ),
+        (
+            'Z',
+            &[
+                "######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....",
+                "#.....", "######",
+            ],
+        ),
+    ],
+};
+
+/// A rectangle of pixels within the image.
+struct Region<'a> {
+    pixels: &'a [bool],
+    width: usize,
+    top: usize,
+    height: usize,
+}
+
+impl Region<'_> {
+    fn is_lit(&self, x: usize, y: usize) -> bool {
+        x < self.width && self.pixels[(self.top + y) * self.width + x]
+    }
+
+    fn is_column_lit(&self, x: usize) -> bool {
+        (0..self.height).any(|y| self.is_lit(x, y))
+    }
+
+    /// The glyph in the columns, without blank columns on either side.
+    fn glyph(&self, columns: std::ops::Range<usize>) -> Vec<String> {
+        let lit = columns
+            .clone()
+            .filter(|&x| self.is_column_lit(x))
+            .collect::<Vec<_>>();
+        let (first, last) = match (lit.first(), lit.last()) {
+            (Some(&first), Some(&last)) => (first, last),
+            _ => return Vec::new(),
+        };
+        (0..self.height)
+            .map(|y| {
+                (first..=last)
+                    .map(|x| if self.is_lit(x, y) { '#' } else { '.' })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+fn trim_glyph(rows: &[&str]) -> Vec<String> {
+    let width = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+    let is_lit = |x: usize| rows.iter().any(|r| r.as_bytes().get(x) == Some(&b'#'));
+    let first = (0..width).find(|&x| is_lit(x)).unwrap_or(0);
+    let last = (0..width).rev().find(|&x| is_lit(x)).unwrap_or(0);
+    rows.iter().map(|r| r[first..=last].to_string()).collect()
+}
+
+/// Splits the region into letter cells starting at `offset`, returning `None` if a lit pixel
+/// is before the first cell.
+fn recognize_at(
+    region: &Region<'_>,
+    font: &Font,
+    offset: usize,
+) -> Option<Vec<Result<char, Vec<String>>>> {
+    if (0..offset).any(|x| region.is_column_lit(x)) {
+        return None;
+    }
+
+    let glyphs = font
+        .glyphs
+        .iter()
+        .map(|(c, rows)| (*c, trim_glyph(rows)))
+        .collect::<Vec<_>>();
+
+    let mut letters = Vec::new();
+    let mut start = offset;
+    while start < region.width {
+        let glyph = region.glyph(start..(start + font.pitch).min(region.width));
+        letters.push(if glyph.is_empty() {
+            Ok(' ')
+        } else {
+            glyphs
+                .iter()
+                .find(|(_, g)| *g == glyph)
+                .map(|(c, _)| *c)
+                .ok_or(glyph)
+        });
+        start += font.pitch;
+    }
+
+    while let Some(Ok(' ')) = letters.last() {
+        letters.pop();
+    }
+    Some(letters)
+}
+
+fn recognize_letters(
+    pixels: &[bool],
+    width: usize,
+) -> Result<Vec<Result<char, Vec<String>>>, Error> {
+    if width == 0 || !pixels.len().is_multiple_of(width) {
+        return Err(Error::InvalidDimensions);
+    }
+    let height = pixels.len() / width;
+
+    let is_row_lit = |y: usize| pixels[y * width..(y + 1) * width].iter().any(|&p| p);
+    let top = (0..height).find(|&y| is_row_lit(y)).unwrap_or(0);
+    let bottom = (0..height)
+        .rev()
+        .find(|&y| is_row_lit(y))
+        .map_or(0, |y| y + 1);
+
+    // Prefer the full height so letters which do not reach the top or bottom row still align.
+    let font = [&SMALL, &LARGE]
+        .iter()
+        .find(|f| f.height == height)
+        .map(|f| (*f, 0))
+        .or_else(|| {
+            [&SMALL, &LARGE]
+                .iter()
+                .find(|f| f.height == bottom.saturating_sub(top))
+                .map(|f| (*f, top))
+        });
+    let (font, top) = font.ok_or_else(|| Error::UnsupportedHeight(bottom.saturating_sub(top)))?;
+
+    let region = Region {
+        pixels,
+        width,
+        top,
+        height: font.height,
+    };
+
+    // Use the alignment which recognizes the most letters.
+    Ok((0..font.pitch)
+        .filter_map(|offset| recognize_at(&region, font, offset))
+        .min_by_key(|letters| letters.iter().filter(|l| l.is_err()).count())
+        .unwrap_or_default())
+}
+
+/// Recognizes the text in row-major pixels, where `true` is a lit pixel.
+pub fn recognize(pixels: &[bool], width: usize) -> Result<String, Error> {
+    let letters = recognize_letters(pixels, width)?;
+
+    let unknown = letters
+        .iter()
+        .enumerate()
+        .filter_map(|(index, l)| {
+            l.as_ref().err().map(|rows| UnknownGlyph {
+                index,
+                pixels: rows.join("\n"),
+            })
+        })
+        .collect::<Vec<_>>();
+    if !unknown.is_empty() {
+        return Err(Error::UnknownGlyphs(unknown));
+    }
+
+    Ok(letters.into_iter().filter_map(Result::ok).collect())
+}
+
+/// Recognizes the text in row-major pixels, replacing unknown glyphs with `?`.
+pub fn recognize_lossy(pixels: &[bool], width: usize) -> Result<String, Error> {
+    Ok(recognize_letters(pixels, width)?
+        .into_iter()
+        .map(|l| l.unwrap_or('?'))
+        .collect())
+}
+
+/// Parses lines of `#` (lit) and any other character (unlit) into pixels and a width.
+#[must_use]
+pub fn parse_pixels(art: &str) -> (Vec<bool>, usize) {
+    let lines = art.lines().collect::<Vec<_>>();
+    let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    let pixels = lines
+        .iter()
+        .flat_map(|l| {
+            let mut row = l.chars().map(|c| c == '#').collect::<Vec<_>>();
+            row.resize(width, false);
+            row
+        })
+        .collect();
+    (pixels, width)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(art: &str) -> Result<String, Error> {
+        let (pixels, width) = parse_pixels(art);
+        recognize(&pixels, width)
+    }
+
+    #[test]
+    fn small_font() {
+        // 2022 day 10
+        let crt = "\
+####.#..#.####.####.####.#..#..##..####.
+#....#..#....#.#.......#.#..#.#..#....#.
+###..####...#..###....#..####.#......#..
+#....#..#..#...#.....#...#..#.#.....#...
+#....#..#.#....#....#....#..#.#..#.#....
+####.#..#.####.#....####.#..#..##..####.";
+        assert_eq!(Ok(String::from("EHZFZHCZ")), read(crt));
+
+        // 2019 day 8, where letters touch
+        let img = "\
+.##..#...##..#.###..#...#
+#..#.#...##.#..#..#.#...#
+#.....#.#.##...###...#.#.
+#......#..#.#..#..#...#..
+#..#...#..#.#..#..#...#..
+.##....#..#..#.###....#..";
+        assert_eq!(Ok(String::from("CYKBY")), read(img));
+
+        // Shifted right with blank rows above and below
+        let hull = "\
+............
+.###....##..
+.#..#....#..
+.###.....#..
+.#..#....#..
+.#..#.#..#..
+.###...##...
+............";
+        assert_eq!(Ok(String::from("BJ")), read(hull));
+    }
+
+    #[test]
+    fn starts_with_narrow_letter() {
+        let art = "\
+.###.#....
+..#..#....
+..#..#....
+..#..#....
+..#..#....
+.###.####.";
+        assert_eq!(Ok(String::from("IL")), read(art));
+    }
+
+    #[test]
+    fn large_font() {
+        let art = "\
+#....#..######
+#....#..#.....
+.#..#...#.....
+.#..#...#.....
+..##....#####.
+..##....#.....
+.#..#...#.....
+.#..#...#.....
+#....#..#.....
+#....#..######";
+        assert_eq!(Ok(String::from("XE")), read(art));
+    }
+
+    #[test]
+    fn unknown_glyphs() {
+        let art = "\
+####.#...
+#..#.#...
+#..#.#...
+#..#.#...
+#..#.#...
+####.####";
+        assert_eq!(
+            Err(Error::UnknownGlyphs(vec![UnknownGlyph {
+                index: 0,
+                pixels: String::from("####\n#..#\n#..#\n#..#\n#..#\n####"),
+            }])),
+            read(art)
+        );
+        let (pixels, width) = parse_pixels(art);
+        assert_eq!(Ok(String::from("?L")), recognize_lossy(&pixels, width));
+
+        assert_eq!(Err(Error::UnsupportedHeight(2)), read("#\n#"));
+        assert_eq!(Err(Error::InvalidDimensions), recognize(&[true; 3], 2));
+    }
+}